@@ -0,0 +1,5624 @@
+use heck::{ToSnakeCase, ToUpperCamelCase};
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2, TokenTree};
+use quote::{quote, quote_spanned, ToTokens};
+use std::collections::HashSet;
+use syn::{
+    ext::IdentExt,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Data, DeriveInput, Field, Fields, GenericParam, Generics, Ident, LitStr, Token, WhereClause,
+};
+
+/// Every option `#[partial(...)]` accepts, listed when an unknown one is found.
+const PARTIAL_OPTIONS: &[&str] = &[
+    "derive",
+    "cfg_derive",
+    "omitted_derive",
+    "omit",
+    "omit_type",
+    "default",
+    "optional",
+    "optional_empty",
+    "all_optional",
+    "wrap_all",
+    "allow_empty",
+    "allow_dead_code",
+    "from_shared",
+    "from_ref",
+    "from_mode",
+    "borrowed",
+    "hash_helper",
+    "cross_eq",
+    "log_missing",
+    "scoped_impls",
+    "try_complete",
+    "try_rebuild",
+    "freeze_optional",
+    "skip_from",
+    "keep_repr",
+    "manual_clone",
+    "accessors",
+    "constructor",
+    "into_params",
+    "field_enum",
+    "string_map",
+    "frozen",
+    "view",
+    "tuple_struct",
+    "zeroize",
+    "redact",
+    "serialize_none",
+    "from_json",
+    "maps_to",
+    "structural_from",
+    "rebuild_fn",
+    "where_bound",
+    "module",
+    "vis",
+    "must_use",
+    "omitted_as",
+    "rename",
+    "forward_attrs",
+    "forward_struct_attrs",
+];
+
+// --- PartialArgs struct and its Parse impl remain the same ---
+// (Included here for completeness, no changes needed in this part)
+/// Represents the arguments for the `#[partial(...)]` attribute.
+/// ... (docs remain the same)
+#[derive(Default)]
+struct PartialArgs {
+    target_name: Option<LitStr>,
+    derive_traits: Vec<syn::Path>,
+    /// `cfg_derive(predicate, Trait, ...)`: traits derived only when the cfg
+    /// predicate holds, through `cfg_attr`.
+    cfg_derives: Vec<(syn::Meta, Vec<syn::Path>)>,
+    /// `where_bound("T: Send", ...)`: extra predicates for the impls and
+    /// methods that involve the full struct.
+    where_bounds: Vec<syn::WherePredicate>,
+    /// `omitted_derive(...)`: traits to derive on the omitted-fields struct.
+    omitted_derive_traits: Vec<syn::Path>,
+    omit_fields: Vec<Ident>,
+    /// `omit_type(...)`: omit every field whose type matches one of these.
+    omit_types: Vec<syn::Type>,
+    /// `default(field = "expr", ...)`: values for omitted fields, used by
+    /// `<rebuild>_defaulted()`.
+    omitted_defaults: Vec<(Ident, syn::Expr)>,
+    optional_fields: Vec<OptionalField>,
+    /// `from_shared`: also convert from `&Arc<Orig>` and `&Rc<Orig>` by cloning.
+    from_shared: bool,
+    /// `from_ref`: also convert from `&Orig` by cloning. Set by
+    /// `from_mode(clone)` too.
+    from_ref: bool,
+    /// `borrowed`: also generate a `<Target>Ref<'_>` view holding references to the fields.
+    borrowed: bool,
+    /// `maps_to = "Other"`: structurally compatible types to convert this partial into.
+    maps_to: Vec<syn::Type>,
+    /// `structural_from(Other, ...)`: same-shaped structs to convert into this partial.
+    structural_from: Vec<syn::Path>,
+    /// `cross_eq`: compare this partial against the full struct with `==`.
+    cross_eq: bool,
+    /// `log_missing`: generate a fallible rebuild that logs the first missing optional field.
+    log_missing: bool,
+    /// `all_optional`: make every non-omitted field optional, producing a patch struct.
+    all_optional: bool,
+    /// `scoped_impls`: emit the generated impls inside an anonymous `const _: () = { ... };` block.
+    scoped_impls: bool,
+    /// `zeroize`: derive `Zeroize` and `ZeroizeOnDrop` on the omitted-fields struct.
+    zeroize: bool,
+    /// `wrap_all(Wrapper)`: store every included field as `Wrapper<T>`,
+    /// converted through `partial_struct::FieldWrapper`.
+    wrap_all: Option<syn::Path>,
+    /// `serialize_none`: do not add `skip_serializing_if` to optional fields
+    /// when the partial derives `Serialize`.
+    serialize_none: bool,
+    /// `from_json`: generate `from_json_value(&serde_json::Value)`.
+    from_json: bool,
+    /// `redact(...)`: implement `Debug` on the omitted-fields struct, printing
+    /// these fields as `<redacted>`.
+    redact_fields: Vec<Ident>,
+    /// `tuple_struct`: also generate a positional `<Target>Tuple` newtype.
+    tuple_struct: bool,
+    /// `rebuild_fn = "name"`: overrides the `to_<orig>` method name.
+    rebuild_fn: Option<Ident>,
+    /// `hash_helper`: give the full struct a `partial_hash()` method hashing
+    /// only this partial's fields.
+    hash_helper: bool,
+    /// `try_complete`: implement `TryFrom<Target>` for the original struct.
+    try_complete: bool,
+    /// `try_rebuild`: generate `try_<rebuild>(omitted)` failing with a
+    /// `<Target>MissingField` enum when an optional field is `None`.
+    try_rebuild: bool,
+    /// `freeze_optional`: generate `complete(omitted...)` failing with every
+    /// optional field that is `None`, collected in `<Target>MissingFields`.
+    freeze_optional: bool,
+    /// `skip_from`: do not implement `From<Orig>` for this partial. Set by
+    /// `from_mode(clone)` too.
+    skip_from: bool,
+    /// `keep_repr`: copy the original struct's `#[repr(...)]` onto this partial.
+    keep_repr: bool,
+    /// `manual_clone`: implement `Clone` by hand, bounded on the field types
+    /// instead of the generic parameters.
+    manual_clone: bool,
+    /// `module = "name"`: emit every generated item inside `pub mod name`.
+    module: Option<Ident>,
+    /// `vis = "pub"`: visibility of the generated types, instead of the
+    /// original struct's.
+    vis: Option<syn::Visibility>,
+    /// `omitted_as(tuple)`: hand out the omitted fields as a tuple in
+    /// declaration order instead of a `<Target>Omitted` struct.
+    omitted_as_tuple: bool,
+    /// `rename(field = "new_name", ...)`: names a kept field differently in
+    /// this partial only.
+    renames: Vec<(Ident, Ident)>,
+    /// `accessors`: generate `field()` and `field_mut()` for optional fields.
+    accessors: bool,
+    /// `frozen`: generate a read-only `Frozen<Target>` newtype with getters,
+    /// plus `freeze()` returning the partial in an `Arc`.
+    frozen: bool,
+    /// `view`: generate `<rebuild>_ref(...)` returning a `<Target>View` that
+    /// borrows every field of the full struct.
+    view: bool,
+    /// `field_enum`: generate a `<Target>Field` enum naming the kept fields.
+    field_enum: bool,
+    /// `string_map`: generate `to_string_map()` and `from_string_map(...)`
+    /// converting to and from a map of `Display`/`FromStr` strings.
+    string_map: bool,
+    /// `allow_empty`: accept a partial that keeps no fields.
+    allow_empty: bool,
+    /// `allow_dead_code`: put `#[allow(dead_code)]` on every generated item.
+    allow_dead_code: bool,
+    /// `constructor`: generate `new(...)` taking the partial's fields in
+    /// declaration order.
+    constructor: bool,
+    /// `into_params`: the rebuild methods take omitted fields as `impl Into<T>`.
+    into_params: bool,
+    /// `must_use(false)`: leave `#[must_use]` off the rebuild methods.
+    skip_must_use: bool,
+    /// `forward_attrs(...)`: only copy field attributes with these names.
+    /// `None` copies every attribute.
+    forward_attrs: Option<Vec<Ident>>,
+    /// `forward_struct_attrs(...)`: copy the struct attributes with these
+    /// names, such as `strum` for a derived `strum::Display`.
+    forward_struct_attrs: Vec<Ident>,
+    /// Span of the whole `#[partial(...)]` attribute, for errors that have no
+    /// more specific token to point at. `None` for the implicit default partial.
+    attr_span: Option<Span>,
+}
+
+impl Parse for PartialArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut target_name = None;
+        let mut derive_traits = Vec::new();
+        let mut omit_fields = Vec::new();
+        let mut omit_types = Vec::new();
+        let mut omitted_defaults = Vec::new();
+        let mut omitted_derive_traits = Vec::new();
+        let mut cfg_derives = Vec::new();
+        let mut where_bounds = Vec::new();
+        let mut redact_fields = Vec::new();
+        let mut from_json = false;
+        let mut optional_fields = Vec::new();
+        let mut from_shared = false;
+        let mut from_ref = false;
+        let mut borrowed = false;
+        let mut maps_to = Vec::new();
+        let mut structural_from = Vec::new();
+        let mut cross_eq = false;
+        let mut log_missing = false;
+        let mut all_optional = false;
+        let mut scoped_impls = false;
+        let mut zeroize = false;
+        let mut tuple_struct = false;
+        let mut rebuild_fn = None;
+        let mut hash_helper = false;
+        let mut try_complete = false;
+        let mut try_rebuild = false;
+        let mut freeze_optional = false;
+        let mut serialize_none = false;
+        let mut wrap_all: Option<syn::Path> = None;
+        let mut skip_from = false;
+        let mut keep_repr = false;
+        let mut manual_clone = false;
+        let mut module = None;
+        let mut vis = None;
+        let mut skip_must_use = false;
+        let mut accessors = false;
+        let mut constructor = false;
+        let mut into_params = false;
+        let mut allow_empty = false;
+        let mut allow_dead_code = false;
+        let mut field_enum = false;
+        let mut string_map = false;
+        let mut view = false;
+        let mut frozen = false;
+        let mut omitted_as_tuple = false;
+        let mut renames = Vec::new();
+        let mut forward_attrs: Option<Vec<Ident>> = None;
+        let mut forward_struct_attrs = Vec::new();
+
+        while !input.is_empty() {
+            let lookahead = input.lookahead1();
+            if lookahead.peek(LitStr) {
+                let name: LitStr = input.parse()?;
+                if target_name.is_some() {
+                    return Err(syn::Error::new(
+                        name.span(),
+                        "the partial struct name is already given in this attribute",
+                    ));
+                }
+                name.parse::<Ident>().map_err(|_| {
+                    syn::Error::new(
+                        name.span(),
+                        format!("`{}` is not a valid struct name", name.value()),
+                    )
+                })?;
+                target_name = Some(name);
+            } else if lookahead.peek(Ident) {
+                let key: Ident = input.parse()?;
+                match key.to_string().as_str() {
+                    "derive" => {
+                        let content;
+                        syn::parenthesized!(content in input);
+                        derive_traits.extend(
+                            content.parse_terminated(syn::Path::parse_mod_style, Token![,])?,
+                        );
+                    }
+                    "cfg_derive" => {
+                        let content;
+                        syn::parenthesized!(content in input);
+                        let predicate: syn::Meta = content.parse()?;
+                        let _comma: Token![,] = content.parse()?;
+                        let traits =
+                            content.parse_terminated(syn::Path::parse_mod_style, Token![,])?;
+                        if traits.is_empty() {
+                            return Err(content
+                                .error("expected at least one trait after the cfg predicate"));
+                        }
+                        cfg_derives.push((predicate, traits.into_iter().collect()));
+                    }
+                    "where_bound" => {
+                        let content;
+                        syn::parenthesized!(content in input);
+                        for bound in
+                            content.parse_terminated(<LitStr as Parse>::parse, Token![,])?
+                        {
+                            let predicates = bound.parse_with(
+                                Punctuated::<syn::WherePredicate, Token![,]>::parse_terminated,
+                            )?;
+                            where_bounds.extend(predicates);
+                        }
+                    }
+                    "omitted_derive" => {
+                        let content;
+                        syn::parenthesized!(content in input);
+                        omitted_derive_traits.extend(
+                            content.parse_terminated(syn::Path::parse_mod_style, Token![,])?,
+                        );
+                    }
+                    "omit" => omit_fields.extend(parse_ident_list(input)?),
+                    "wrap_all" => {
+                        if wrap_all.is_some() {
+                            return Err(syn::Error::new(
+                                key.span(),
+                                "'wrap_all' is already given in this attribute",
+                            ));
+                        }
+                        let content;
+                        syn::parenthesized!(content in input);
+                        wrap_all = Some(content.call(syn::Path::parse_mod_style)?);
+                    }
+                    "redact" => redact_fields.extend(parse_ident_list(input)?),
+                    "omit_type" => {
+                        let content;
+                        syn::parenthesized!(content in input);
+                        omit_types.extend(content.parse_terminated(syn::Type::parse, Token![,])?);
+                    }
+                    "forward_attrs" => forward_attrs
+                        .get_or_insert_with(Vec::new)
+                        .extend(parse_ident_list(input)?),
+                    "forward_struct_attrs" => forward_struct_attrs.extend(parse_ident_list(input)?),
+                    "optional_empty" => {
+                        optional_fields.extend(parse_ident_list(input)?.into_iter().map(|ident| {
+                            OptionalField {
+                                ident,
+                                via: None,
+                                default: Some(syn::parse_quote!(
+                                    ::core::default::Default::default()
+                                )),
+                                empty_is_none: true,
+                            }
+                        }));
+                    }
+                    "optional" => {
+                        let content;
+                        syn::parenthesized!(content in input);
+                        optional_fields
+                            .extend(content.parse_terminated(OptionalField::parse, Token![,])?);
+                    }
+                    "from_shared" => from_shared = true,
+                    "from_ref" => from_ref = true,
+                    "borrowed" => borrowed = true,
+                    "cross_eq" => cross_eq = true,
+                    "all_optional" => all_optional = true,
+                    "scoped_impls" => scoped_impls = true,
+                    "tuple_struct" => tuple_struct = true,
+                    "hash_helper" => hash_helper = true,
+                    "try_complete" => try_complete = true,
+                    "try_rebuild" => try_rebuild = true,
+                    "freeze_optional" => freeze_optional = true,
+                    "serialize_none" => serialize_none = true,
+                    "skip_from" => skip_from = true,
+                    "keep_repr" => keep_repr = true,
+                    "manual_clone" => manual_clone = true,
+                    "accessors" => accessors = true,
+                    "constructor" => constructor = true,
+                    "into_params" => into_params = true,
+                    "allow_empty" => allow_empty = true,
+                    "allow_dead_code" => allow_dead_code = true,
+                    "field_enum" => field_enum = true,
+                    "string_map" => string_map = true,
+                    "view" => view = true,
+                    "frozen" => frozen = true,
+                    "log_missing" => {
+                        if !cfg!(feature = "log") {
+                            return Err(syn::Error::new(
+                                key.span(),
+                                "'log_missing' requires the `log` feature of partial_struct",
+                            ));
+                        }
+                        log_missing = true;
+                    }
+                    "from_json" => {
+                        if !cfg!(feature = "json") {
+                            return Err(syn::Error::new(
+                                key.span(),
+                                "'from_json' requires the `json` feature of partial_struct",
+                            ));
+                        }
+                        from_json = true;
+                    }
+                    "zeroize" => {
+                        if !cfg!(feature = "zeroize") {
+                            return Err(syn::Error::new(
+                                key.span(),
+                                "'zeroize' requires the `zeroize` feature of partial_struct",
+                            ));
+                        }
+                        zeroize = true;
+                    }
+                    "rebuild_fn" => {
+                        if rebuild_fn.is_some() {
+                            return Err(syn::Error::new(
+                                key.span(),
+                                "'rebuild_fn' is already given in this attribute",
+                            ));
+                        }
+                        let _eq: Token![=] = input.parse()?;
+                        let name: LitStr = input.parse()?;
+                        rebuild_fn = Some(name.parse()?);
+                    }
+                    "default" => {
+                        let content;
+                        syn::parenthesized!(content in input);
+                        let entries = content.parse_terminated(
+                            |entry: ParseStream| {
+                                let field: Ident = entry.parse()?;
+                                let _eq: Token![=] = entry.parse()?;
+                                let expr: LitStr = entry.parse()?;
+                                Ok((field, expr.parse::<syn::Expr>()?))
+                            },
+                            Token![,],
+                        )?;
+                        omitted_defaults.extend(entries);
+                    }
+                    "rename" => {
+                        let content;
+                        syn::parenthesized!(content in input);
+                        let entries = content.parse_terminated(
+                            |entry: ParseStream| {
+                                let field: Ident = entry.parse()?;
+                                let _eq: Token![=] = entry.parse()?;
+                                let name: LitStr = entry.parse()?;
+                                let new_name = name.parse::<Ident>().map_err(|_| {
+                                    syn::Error::new(
+                                        name.span(),
+                                        format!("`{}` is not a valid field name", name.value()),
+                                    )
+                                })?;
+                                Ok((field, new_name))
+                            },
+                            Token![,],
+                        )?;
+                        renames.extend(entries);
+                    }
+                    "omitted_as" => {
+                        let content;
+                        syn::parenthesized!(content in input);
+                        let shape = content.call(Ident::parse_any)?;
+                        omitted_as_tuple = match shape.to_string().as_str() {
+                            "tuple" => true,
+                            "struct" => false,
+                            _ => {
+                                return Err(syn::Error::new(
+                                    shape.span(),
+                                    "Expected 'omitted_as(tuple)' or 'omitted_as(struct)'",
+                                ))
+                            }
+                        };
+                    }
+                    // `from_mode(clone)` is `from_ref` without the moving
+                    // `From<Orig>`; `from_mode(move)` is the default.
+                    "from_mode" => {
+                        let content;
+                        syn::parenthesized!(content in input);
+                        let mode: Ident = content.parse()?;
+                        match mode.to_string().as_str() {
+                            "clone" => {
+                                skip_from = true;
+                                from_ref = true;
+                            }
+                            "move" => {}
+                            _ => {
+                                return Err(syn::Error::new(
+                                    mode.span(),
+                                    "Expected 'from_mode(move)' or 'from_mode(clone)'",
+                                ))
+                            }
+                        }
+                    }
+                    "must_use" => {
+                        let content;
+                        syn::parenthesized!(content in input);
+                        let value: syn::LitBool = content.parse()?;
+                        skip_must_use = !value.value;
+                    }
+                    "module" => {
+                        if module.is_some() {
+                            return Err(syn::Error::new(
+                                key.span(),
+                                "'module' is already given in this attribute",
+                            ));
+                        }
+                        let _eq: Token![=] = input.parse()?;
+                        let name: LitStr = input.parse()?;
+                        module = Some(name.parse()?);
+                    }
+                    "vis" => {
+                        if vis.is_some() {
+                            return Err(syn::Error::new(
+                                key.span(),
+                                "'vis' is already given in this attribute",
+                            ));
+                        }
+                        let _eq: Token![=] = input.parse()?;
+                        let value: LitStr = input.parse()?;
+                        vis = Some(value.parse()?);
+                    }
+                    "structural_from" => {
+                        let content;
+                        syn::parenthesized!(content in input);
+                        structural_from
+                            .extend(content.parse_terminated(syn::Path::parse, Token![,])?);
+                    }
+                    "maps_to" => {
+                        let _eq: Token![=] = input.parse()?;
+                        let target: LitStr = input.parse()?;
+                        maps_to.push(target.parse()?);
+                    }
+                    _ => {
+                        return Err(syn::Error::new(
+                            key.span(),
+                            format!(
+                                "unknown partial option `{}`; expected one of: {}",
+                                key,
+                                PARTIAL_OPTIONS.join(", ")
+                            ),
+                        ))
+                    }
+                }
+            } else {
+                return Err(lookahead.error());
+            }
+
+            if input.peek(Token![,]) {
+                let _comma: Token![,] = input.parse()?;
+            }
+        }
+        if zeroize && omitted_as_tuple {
+            return Err(input.error("'zeroize' derives on the omitted-fields struct and cannot be combined with 'omitted_as(tuple)'"));
+        }
+        if let Some(path) = omitted_derive_traits.first().filter(|_| omitted_as_tuple) {
+            return Err(syn::Error::new_spanned(
+                path,
+                "'omitted_derive' derives on the omitted-fields struct and cannot be combined with 'omitted_as(tuple)'",
+            ));
+        }
+        if let Some(path) = omitted_derive_traits
+            .iter()
+            .filter(|_| !redact_fields.is_empty())
+            .find(|path| is_std_derive(path, "Debug"))
+        {
+            return Err(syn::Error::new_spanned(path, "'redact' implements Debug on the omitted-fields struct, so it cannot also be derived"));
+        }
+        if let Some(field) = redact_fields.first().filter(|_| omitted_as_tuple) {
+            return Err(syn::Error::new(
+                field.span(),
+                "'redact' implements Debug on the omitted-fields struct and cannot be combined with 'omitted_as(tuple)'",
+            ));
+        }
+        // The hash helper hashes optional fields as `Option`, which a custom
+        // wrapper need not match.
+        if let Some(via) = optional_fields
+            .iter()
+            .filter(|_| hash_helper)
+            .find_map(|optional| optional.via.as_ref())
+        {
+            return Err(syn::Error::new_spanned(
+                via,
+                "'hash_helper' cannot be combined with optional fields stored 'via' a wrapper",
+            ));
+        }
+        // These generate types or conversions that move kept fields as their
+        // plain type, which wrapped fields no longer are. `hash_helper`
+        // hashes the plain values, which a derived Hash on the wrapped
+        // partial would not match.
+        if let Some(wrapper) = &wrap_all {
+            let unsupported = [
+                ("borrowed", borrowed),
+                ("hash_helper", hash_helper),
+                ("tuple_struct", tuple_struct),
+                ("from_json", from_json),
+                ("structural_from", !structural_from.is_empty()),
+                ("maps_to", !maps_to.is_empty()),
+            ];
+            if let Some((name, _)) = unsupported.iter().find(|(_, given)| *given) {
+                return Err(syn::Error::new_spanned(
+                    wrapper,
+                    format!("'wrap_all' cannot be combined with '{}'", name),
+                ));
+            }
+        }
+        Ok(PartialArgs {
+            target_name,
+            derive_traits,
+            cfg_derives,
+            where_bounds,
+            omitted_derive_traits,
+            omit_fields,
+            omit_types,
+            omitted_defaults,
+            optional_fields,
+            from_shared,
+            from_ref,
+            borrowed,
+            maps_to,
+            structural_from,
+            cross_eq,
+            log_missing,
+            all_optional,
+            scoped_impls,
+            zeroize,
+            redact_fields,
+            from_json,
+            tuple_struct,
+            rebuild_fn,
+            hash_helper,
+            try_complete,
+            try_rebuild,
+            freeze_optional,
+            serialize_none,
+            wrap_all,
+            skip_from,
+            keep_repr,
+            manual_clone,
+            module,
+            vis,
+            skip_must_use,
+            accessors,
+            constructor,
+            into_params,
+            allow_empty,
+            allow_dead_code,
+            field_enum,
+            string_map,
+            view,
+            frozen,
+            omitted_as_tuple,
+            renames,
+            forward_attrs,
+            forward_struct_attrs,
+            attr_span: None,
+        })
+    }
+}
+
+/// A field listed in `optional(...)`, either bare (`email`), stored in a
+/// wrapper other than `Option` (`email via Undefinable`), and/or with a
+/// default used when rebuilding from `None` (`role = Role::User`).
+///
+/// Fields listed in `optional_empty(...)` also land here, with
+/// `Default::default()` as their default and `empty_is_none` set.
+#[derive(Clone)]
+struct OptionalField {
+    ident: Ident,
+    via: Option<syn::Path>,
+    default: Option<syn::Expr>,
+    /// An empty value of the full struct becomes `None` in the partial.
+    empty_is_none: bool,
+}
+
+impl Parse for OptionalField {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ident = input.parse()?;
+        let via = if input.peek(Ident) && input.fork().parse::<Ident>()? == "via" {
+            let _via: Ident = input.parse()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        let default = if input.peek(Token![=]) {
+            let _eq: Token![=] = input.parse()?;
+            // A string literal default converts into the field type, so
+            // `"n/a"` fills a `String` field without `.to_string()`.
+            match input.parse()? {
+                syn::Expr::Lit(lit) if matches!(lit.lit, syn::Lit::Str(_)) => {
+                    Some(syn::parse_quote! { ::core::convert::Into::into(#lit) })
+                }
+                expr => Some(expr),
+            }
+        } else {
+            None
+        };
+        Ok(OptionalField {
+            ident,
+            via,
+            default,
+            empty_is_none: false,
+        })
+    }
+}
+
+/// The struct-level `#[partial_common(omit(...), optional(...))]` attribute,
+/// whose entries are merged into every partial of the struct.
+#[derive(Default)]
+struct CommonArgs {
+    omit_fields: Vec<Ident>,
+    optional_fields: Vec<OptionalField>,
+}
+
+impl Parse for CommonArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut common = CommonArgs::default();
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            match key.to_string().as_str() {
+                "omit" => common.omit_fields.extend(parse_ident_list(input)?),
+                "optional" => {
+                    let content;
+                    syn::parenthesized!(content in input);
+                    common
+                        .optional_fields
+                        .extend(content.parse_terminated(OptionalField::parse, Token![,])?);
+                }
+                _ => return Err(syn::Error::new(key.span(), "Expected 'omit' or 'optional'")),
+            }
+            if !input.is_empty() {
+                let _comma: Token![,] = input.parse()?;
+            }
+        }
+        Ok(common)
+    }
+}
+
+impl CommonArgs {
+    /// Adds the common entries to `partial_args`, skipping fields that the
+    /// partial already lists in its own `omit(...)` or `optional(...)`.
+    fn merge_into(&self, partial_args: &mut PartialArgs) {
+        let listed: HashSet<String> = partial_args
+            .omit_fields
+            .iter()
+            .chain(
+                partial_args
+                    .optional_fields
+                    .iter()
+                    .map(|optional| &optional.ident),
+            )
+            .map(|ident| ident.unraw().to_string())
+            .collect();
+        let unlisted = |ident: &Ident| !listed.contains(&ident.unraw().to_string());
+        partial_args.omit_fields.extend(
+            self.omit_fields
+                .iter()
+                .filter(|ident| unlisted(ident))
+                .cloned(),
+        );
+        partial_args.optional_fields.extend(
+            self.optional_fields
+                .iter()
+                .filter(|optional| unlisted(&optional.ident))
+                .cloned(),
+        );
+    }
+}
+
+/// How an optional field is stored in a partial: as `Option<T>`, or in a
+/// wrapper reached through `partial_struct::OptionLike<T>`.
+struct OptionalRepr<'a> {
+    ty: &'a syn::Type,
+    via: Option<&'a syn::Path>,
+    empty_is_none: bool,
+}
+
+impl<'a> OptionalRepr<'a> {
+    fn of(
+        field: &'a Field,
+        optionals: &std::collections::HashMap<String, &'a OptionalField>,
+    ) -> Self {
+        let optional = field
+            .ident
+            .as_ref()
+            .and_then(|ident| optionals.get(&ident.unraw().to_string()).copied());
+        OptionalRepr {
+            ty: &field.ty,
+            via: optional.and_then(|optional| optional.via.as_ref()),
+            empty_is_none: optional.is_some_and(|optional| optional.empty_is_none),
+        }
+    }
+
+    /// The type of the field in the partial.
+    fn field_ty(&self) -> TokenStream2 {
+        let ty = self.ty;
+        match self.via {
+            Some(via) => quote! { #via<#ty> },
+            None => quote! { Option<#ty> },
+        }
+    }
+
+    fn option_like(&self) -> TokenStream2 {
+        let ty = self.ty;
+        let field_ty = self.field_ty();
+        quote! { <#field_ty as ::partial_struct::OptionLike<#ty>> }
+    }
+
+    /// Wraps a present value.
+    fn wrap(&self, value: TokenStream2) -> TokenStream2 {
+        self.wrap_option(quote! { Some(#value) })
+    }
+
+    /// Wraps a value taken from the full struct, which is absent when empty
+    /// for `optional_empty` fields.
+    fn wrap_full(&self, value: TokenStream2) -> TokenStream2 {
+        if self.empty_is_none {
+            self.wrap_option(quote! { Some(#value).filter(|value| !value.is_empty()) })
+        } else {
+            self.wrap(value)
+        }
+    }
+
+    /// Converts an `Option<T>` into the stored representation.
+    fn wrap_option(&self, option: TokenStream2) -> TokenStream2 {
+        match self.via {
+            Some(_) => {
+                let option_like = self.option_like();
+                quote! { #option_like::from_option(#option) }
+            }
+            None => option,
+        }
+    }
+
+    /// Converts the stored value into an `Option<T>`.
+    fn take(&self, value: TokenStream2) -> TokenStream2 {
+        match self.via {
+            Some(_) => {
+                let option_like = self.option_like();
+                quote! { #option_like::into_option(#value) }
+            }
+            None => value,
+        }
+    }
+
+    /// Borrows a field of the full struct as the `Option<&T>` the partial
+    /// would hold for it.
+    fn borrow_full(&self, place: TokenStream2) -> TokenStream2 {
+        if self.empty_is_none {
+            quote! { Some(&#place).filter(|value| !value.is_empty()) }
+        } else {
+            quote! { Some(&#place) }
+        }
+    }
+
+    /// Mutably borrows the stored place as an `Option<&mut T>`.
+    fn borrow_mut(&self, place: TokenStream2) -> TokenStream2 {
+        match self.via {
+            Some(_) => {
+                let option_like = self.option_like();
+                quote! { #option_like::as_option_mut(&mut #place) }
+            }
+            None => quote! { #place.as_mut() },
+        }
+    }
+
+    /// Borrows the stored place as an `Option<&T>`.
+    fn borrow(&self, place: TokenStream2) -> TokenStream2 {
+        match self.via {
+            Some(_) => {
+                let option_like = self.option_like();
+                quote! { #option_like::as_option(&#place) }
+            }
+            None => quote! { #place.as_ref() },
+        }
+    }
+}
+
+/// For a `Box` field type, the owned value the box can be built from with
+/// `Into`: `T` for `Box<T>`, `String` for `Box<str>` and `Vec<T>` for
+/// `Box<[T]>`. Trait objects have no owned form and are left alone. `Arc`
+/// and `Rc` are not unwrapped, since callers often hold one to share already.
+fn boxed_inner(ty: &syn::Type) -> Option<syn::Type> {
+    let syn::Type::Path(type_path) = ty else {
+        return None;
+    };
+    if type_path.qself.is_some() {
+        return None;
+    }
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Box" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    let [syn::GenericArgument::Type(inner)] = args.args.iter().collect::<Vec<_>>()[..] else {
+        return None;
+    };
+    Some(match inner {
+        syn::Type::Path(path) if path.qself.is_none() && path.path.is_ident("str") => {
+            syn::parse_quote!(::std::string::String)
+        }
+        syn::Type::Slice(slice) => {
+            let elem = &slice.elem;
+            syn::parse_quote!(::std::vec::Vec<#elem>)
+        }
+        syn::Type::TraitObject(_) => return None,
+        inner => inner.clone(),
+    })
+}
+
+/// Whether a field's type matches an `omit_type(...)` entry. Types compare by
+/// their tokens, except that a path written without generic arguments matches
+/// any arguments and any leading path: `PhantomData` matches
+/// `std::marker::PhantomData<T>`.
+fn type_matches(ty: &syn::Type, listed: &syn::Type) -> bool {
+    if quote!(#ty).to_string() == quote!(#listed).to_string() {
+        return true;
+    }
+    let (syn::Type::Path(ty), syn::Type::Path(listed)) = (ty, listed) else {
+        return false;
+    };
+    if ty.qself.is_some()
+        || listed.qself.is_some()
+        || listed
+            .path
+            .segments
+            .iter()
+            .any(|segment| !segment.arguments.is_none())
+    {
+        return false;
+    }
+    let idents = |path: &syn::Path| {
+        path.segments
+            .iter()
+            .map(|segment| segment.ident.to_string())
+            .collect::<Vec<_>>()
+    };
+    idents(&ty.path).ends_with(&idents(&listed.path))
+}
+
+/// Whether a field type is `Option<T>`, written bare or through
+/// `std`/`core`.
+fn is_option(ty: &syn::Type) -> bool {
+    let syn::Type::Path(type_path) = ty else {
+        return false;
+    };
+    let path = &type_path.path;
+    let through_std = path.segments.len() == 3
+        && path
+            .segments
+            .first()
+            .is_some_and(|first| first.ident == "std" || first.ident == "core")
+        && path.segments[1].ident == "option";
+    type_path.qself.is_none()
+        && (path.segments.len() == 1 || through_std)
+        && path.segments.last().is_some_and(|last| {
+            last.ident == "Option" && matches!(&last.arguments, syn::PathArguments::AngleBracketed(args) if args.args.len() == 1)
+        })
+}
+
+/// Represents the arguments for a field-level `#[partial(...)]` attribute.
+#[derive(Default)]
+struct FieldArgs {
+    /// `sort_key`: order partials deriving `Ord`/`PartialOrd` by this field.
+    sort_key: bool,
+    /// `#[partial_group(...)]`: snake_case names of the only partials that
+    /// keep this field. Empty means every partial keeps it.
+    groups: Vec<Ident>,
+    /// `#[partial_flatten(...)]`: inline the embedded struct's fields.
+    flatten: Option<Flatten>,
+    /// `#[partial_default(expr)]`: value for this field in partials that
+    /// omit it, like a `default(...)` entry.
+    default: Option<syn::Expr>,
+}
+
+/// Whether a `derive(...)` entry names the standard trait `name`, either bare
+/// (`Clone`) or through `std`/`core` (`std::clone::Clone`).
+fn is_std_derive(path: &syn::Path, name: &str) -> bool {
+    path.is_ident(name)
+        || (path
+            .segments
+            .first()
+            .is_some_and(|first| first.ident == "std" || first.ident == "core")
+            && path.segments.last().is_some_and(|last| last.ident == name))
+}
+
+/// Adjusts a visibility written next to the original struct for an item one
+/// module deeper, inside the `module = "..."` module.
+fn vis_in_module(vis: &syn::Visibility) -> syn::Visibility {
+    match vis {
+        syn::Visibility::Inherited => syn::parse_quote!(pub(super)),
+        syn::Visibility::Restricted(restricted) => {
+            let path = &restricted.path;
+            if path.is_ident("self") {
+                syn::parse_quote!(pub(super))
+            } else if path
+                .segments
+                .first()
+                .is_some_and(|first| first.ident == "super")
+            {
+                syn::parse_quote!(pub(in super::#path))
+            } else if path
+                .segments
+                .first()
+                .is_some_and(|first| first.ident == "self")
+            {
+                let rest = path.segments.iter().skip(1);
+                syn::parse_quote!(pub(in super #(::#rest)*))
+            } else {
+                vis.clone()
+            }
+        }
+        syn::Visibility::Public(_) => vis.clone(),
+    }
+}
+
+/// Rewrites a type path for use in expressions and patterns:
+/// `Wrapper<T> { .. }` is not valid there, `Wrapper::<T> { .. }` is.
+fn expr_path(path: &syn::Path) -> syn::Path {
+    let mut path = path.clone();
+    for segment in path.segments.iter_mut() {
+        if let syn::PathArguments::AngleBracketed(args) = &mut segment.arguments {
+            args.colon2_token = Some(Default::default());
+        }
+    }
+    path
+}
+
+/// An embedded struct whose fields are inlined into the partials.
+///
+/// A derive macro only sees the struct it is attached to, so the embedded
+/// struct's fields are spelled out in the attribute.
+struct Flatten {
+    /// The field's type as an expression path, used to rebuild it.
+    path: syn::Path,
+    /// The embedded struct's fields, as listed in the attribute.
+    fields: Vec<Field>,
+}
+
+impl Flatten {
+    fn from_attr(field: &Field, attr: &syn::Attribute) -> syn::Result<Self> {
+        if matches!(attr.meta, syn::Meta::Path(_)) {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "`#[partial_flatten]` needs the embedded struct's fields, as in `#[partial_flatten(street: String, city: String)]`",
+            ));
+        }
+        let path = match &field.ty {
+            syn::Type::Path(type_path) if type_path.qself.is_none() => expr_path(&type_path.path),
+            other => {
+                return Err(syn::Error::new_spanned(
+                    other,
+                    "`#[partial_flatten]` fields must have a struct type",
+                ))
+            }
+        };
+        let fields = attr.parse_args_with(|input: ParseStream| {
+            Punctuated::<Field, Token![,]>::parse_terminated_with(input, Field::parse_named)
+        })?;
+        Ok(Flatten {
+            path,
+            fields: fields.into_iter().collect(),
+        })
+    }
+
+    /// Builds the embedded struct, taking each field's value from `value`.
+    fn rebuild(&self, value: impl Fn(&Option<Ident>) -> TokenStream2) -> TokenStream2 {
+        let path = &self.path;
+        let assignments = self.fields.iter().map(|field| {
+            let ident = &field.ident;
+            let value = value(ident);
+            quote! { #ident: #value }
+        });
+        quote! { #path { #(#assignments),* } }
+    }
+}
+
+impl Parse for FieldArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut args = FieldArgs::default();
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            match key.to_string().as_str() {
+                "sort_key" => args.sort_key = true,
+                _ => return Err(syn::Error::new(key.span(), "Expected 'sort_key'")),
+            }
+
+            if input.peek(Token![,]) {
+                let _comma: Token![,] = input.parse()?;
+            }
+        }
+        Ok(args)
+    }
+}
+
+impl FieldArgs {
+    /// Merges every `#[partial(...)]`, `#[partial_group(...)]`,
+    /// `#[partial_flatten(...)]` and `#[partial_default(...)]` attribute on `field`.
+    fn from_field(field: &Field) -> syn::Result<Self> {
+        let mut merged = FieldArgs::default();
+        for attr in field.attrs.iter() {
+            if attr.path().is_ident("partial") {
+                let args: FieldArgs = attr.parse_args()?;
+                merged.sort_key |= args.sort_key;
+            } else if attr.path().is_ident("partial_group") {
+                let groups =
+                    attr.parse_args_with(Punctuated::<Ident, Token![,]>::parse_terminated)?;
+                merged.groups.extend(groups);
+            } else if attr.path().is_ident("partial_flatten") {
+                merged.flatten = Some(Flatten::from_attr(field, attr)?);
+            } else if attr.path().is_ident("partial_default") {
+                if merged.default.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        attr,
+                        "`#[partial_default]` is given more than once",
+                    ));
+                }
+                merged.default = Some(attr.parse_args()?);
+            }
+        }
+        Ok(merged)
+    }
+}
+
+/// How a field of the original struct appears in one generated partial.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FieldKind {
+    Included,
+    Optional,
+    Omitted,
+}
+
+/// Field attributes that configure the derive.
+const FIELD_HELPER_ATTRS: &[&str] = &[
+    "partial",
+    "partial_group",
+    "partial_flatten",
+    "partial_default",
+];
+
+/// Returns the attributes of `field` that are copied onto generated fields.
+///
+/// Field-level `#[partial(...)]`, `#[partial_group(...)]`,
+/// `#[partial_flatten(...)]` and `#[partial_default(...)]` attributes configure the derive itself and would
+/// not resolve on the generated structs. With an `allowlist`, only attributes
+/// named in it are copied, plus doc comments and `#[deprecated]` so the
+/// generated fields stay documented and keep warning their users.
+fn forwarded_attrs<'a>(
+    field: &'a Field,
+    allowlist: Option<&'a [Ident]>,
+) -> impl Iterator<Item = &'a syn::Attribute> {
+    field.attrs.iter().filter(move |attr| {
+        let path = attr.path();
+        if FIELD_HELPER_ATTRS.iter().any(|name| path.is_ident(name)) {
+            return false;
+        }
+        match allowlist {
+            Some(allowlist) => {
+                path.is_ident("doc")
+                    || path.is_ident("deprecated")
+                    || allowlist.iter().any(|name| path.is_ident(name))
+            }
+            None => true,
+        }
+    })
+}
+
+/// Parses a parenthesized, comma-separated list of identifiers, as in `omit(a, b)`.
+fn parse_ident_list(input: ParseStream) -> syn::Result<Punctuated<Ident, Token![,]>> {
+    let content;
+    syn::parenthesized!(content in input);
+    content.parse_terminated(Ident::parse, Token![,])
+}
+
+/// Lifetimes and identifiers mentioned by a piece of syntax.
+///
+/// Used to work out which of the original struct's generic parameters a subset
+/// of its fields actually depends on.
+#[derive(Default)]
+struct GenericRefs {
+    lifetimes: HashSet<String>,
+    idents: HashSet<String>,
+}
+
+impl GenericRefs {
+    fn collect(&mut self, tokens: TokenStream2) {
+        let mut iter = tokens.into_iter();
+        while let Some(tt) = iter.next() {
+            match tt {
+                TokenTree::Group(group) => self.collect(group.stream()),
+                TokenTree::Ident(ident) => {
+                    self.idents.insert(ident.to_string());
+                }
+                TokenTree::Punct(punct) if punct.as_char() == '\'' => {
+                    if let Some(TokenTree::Ident(ident)) = iter.next() {
+                        self.lifetimes.insert(ident.to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn mentions(&self, param: &GenericParam) -> bool {
+        match param {
+            GenericParam::Lifetime(def) => self.lifetimes.contains(&def.lifetime.ident.to_string()),
+            GenericParam::Type(ty) => self.idents.contains(&ty.ident.to_string()),
+            GenericParam::Const(cnst) => self.idents.contains(&cnst.ident.to_string()),
+        }
+    }
+}
+
+/// Returns the subset of `generics` needed by a struct made of `fields`.
+///
+/// A parameter is kept when a field type mentions it. Bounds and where-clause
+/// predicates are kept only when every parameter they mention is kept. This
+/// avoids "parameter is never used" errors on generated structs that drop
+/// every field using a parameter.
+fn generics_for_fields(generics: &Generics, fields: &[&Field]) -> Generics {
+    let mut refs = GenericRefs::default();
+    for field in fields {
+        refs.collect(field.ty.to_token_stream());
+    }
+    let only_kept = |tokens: TokenStream2| {
+        let mut bound_refs = GenericRefs::default();
+        bound_refs.collect(tokens);
+        generics
+            .params
+            .iter()
+            .all(|param| !bound_refs.mentions(param) || refs.mentions(param))
+    };
+
+    let params = generics
+        .params
+        .iter()
+        .filter(|param| refs.mentions(param))
+        .cloned()
+        .map(|mut param| {
+            match &mut param {
+                GenericParam::Lifetime(def) => {
+                    def.bounds = std::mem::take(&mut def.bounds)
+                        .into_iter()
+                        .filter(|bound| only_kept(bound.to_token_stream()))
+                        .collect();
+                    if def.bounds.is_empty() {
+                        def.colon_token = None;
+                    }
+                }
+                GenericParam::Type(ty) => {
+                    ty.bounds = std::mem::take(&mut ty.bounds)
+                        .into_iter()
+                        .filter(|bound| only_kept(bound.to_token_stream()))
+                        .collect();
+                    if ty.bounds.is_empty() {
+                        ty.colon_token = None;
+                    }
+                }
+                GenericParam::Const(_) => {}
+            }
+            param
+        })
+        .collect();
+    let where_clause = generics
+        .where_clause
+        .as_ref()
+        .map(|where_clause| WhereClause {
+            where_token: where_clause.where_token,
+            predicates: where_clause
+                .predicates
+                .iter()
+                .filter(|predicate| only_kept(predicate.to_token_stream()))
+                .cloned()
+                .collect(),
+        });
+
+    Generics {
+        lt_token: generics.lt_token,
+        params,
+        gt_token: generics.gt_token,
+        where_clause,
+    }
+}
+
+/// Returns the parameters of `generics` that are missing from `subset`,
+/// stripped of defaults so they can be declared on a method.
+fn extra_method_params(generics: &Generics, subset: &Generics) -> Vec<GenericParam> {
+    let subset_names: HashSet<String> = subset.params.iter().map(generic_param_name).collect();
+    generics
+        .params
+        .iter()
+        .filter(|param| !subset_names.contains(&generic_param_name(param)))
+        .cloned()
+        .map(|mut param| {
+            match &mut param {
+                GenericParam::Type(ty) => {
+                    ty.eq_token = None;
+                    ty.default = None;
+                }
+                GenericParam::Const(cnst) => {
+                    cnst.eq_token = None;
+                    cnst.default = None;
+                }
+                GenericParam::Lifetime(_) => {}
+            }
+            param
+        })
+        .collect()
+}
+
+fn generic_param_name(param: &GenericParam) -> String {
+    match param {
+        GenericParam::Lifetime(def) => def.lifetime.to_string(),
+        GenericParam::Type(ty) => ty.ident.to_string(),
+        GenericParam::Const(cnst) => cnst.ident.to_string(),
+    }
+}
+
+/// Removes default field values (`name: Type = expr`, still unstable) from
+/// the body of a struct so syn can parse it, returning them by field name.
+fn split_field_defaults(
+    input: TokenStream2,
+) -> (
+    TokenStream2,
+    std::collections::HashMap<String, TokenStream2>,
+) {
+    let mut defaults = std::collections::HashMap::new();
+    let mut tokens: Vec<TokenTree> = input.into_iter().collect();
+    // The field list is the last brace group of the item.
+    let Some(body) = tokens.iter_mut().rev().find_map(|token| match token {
+        TokenTree::Group(group) if group.delimiter() == proc_macro2::Delimiter::Brace => {
+            Some(group)
+        }
+        _ => None,
+    }) else {
+        return (tokens.into_iter().collect(), defaults);
+    };
+
+    let mut stripped = TokenStream2::new();
+    let mut field = Vec::new();
+    let mut default: Option<Vec<TokenTree>> = None;
+    // `<`/`>` are not groups, so commas and `=` inside generic arguments
+    // (`Iterator<Item = u8>`) are told apart by tracking their depth.
+    let mut angle_depth = 0usize;
+    let mut prev: Option<TokenTree> = None;
+    let mut finish = |field: &mut Vec<TokenTree>,
+                      default: Option<Vec<TokenTree>>,
+                      stripped: &mut TokenStream2| {
+        if let Some(value) = default {
+            let name = field
+                .iter()
+                .take_while(
+                    |token| !matches!(token, TokenTree::Punct(punct) if punct.as_char() == ':'),
+                )
+                .filter_map(|token| match token {
+                    TokenTree::Ident(ident) => Some(ident.unraw().to_string()),
+                    _ => None,
+                })
+                .last();
+            if let Some(name) = name {
+                defaults.insert(name, value.into_iter().collect());
+            }
+        }
+        stripped.extend(field.drain(..));
+    };
+    for token in body.stream() {
+        let punct = match &token {
+            TokenTree::Punct(punct) => Some(punct.as_char()),
+            _ => None,
+        };
+        let after_path_sep = matches!(&prev, Some(TokenTree::Punct(p)) if p.as_char() == ':' && p.spacing() == proc_macro2::Spacing::Alone)
+            && default.is_some();
+        let after_arrow = matches!(&prev, Some(TokenTree::Punct(p)) if p.as_char() == '-' && p.spacing() == proc_macro2::Spacing::Joint);
+        prev = Some(token.clone());
+        match punct {
+            // In a default value only a turbofish opens generic arguments.
+            Some('<') if default.is_none() || after_path_sep => angle_depth += 1,
+            Some('>') if angle_depth > 0 && !after_arrow => angle_depth -= 1,
+            Some(',') if angle_depth == 0 => {
+                finish(&mut field, default.take(), &mut stripped);
+                stripped.extend([token]);
+                continue;
+            }
+            Some('=') if angle_depth == 0 && default.is_none() => {
+                default = Some(Vec::new());
+                continue;
+            }
+            _ => {}
+        }
+        match &mut default {
+            Some(value) => value.push(token),
+            None => field.push(token),
+        }
+    }
+    finish(&mut field, default.take(), &mut stripped);
+
+    let mut group = proc_macro2::Group::new(body.delimiter(), stripped);
+    group.set_span(body.span());
+    *body = group;
+    (tokens.into_iter().collect(), defaults)
+}
+
+/// Derives one or more partial versions of the annotated struct.
+/// ... (docs remain the same)
+#[proc_macro_derive(
+    Partial,
+    attributes(
+        omit,
+        partial,
+        partial_common,
+        partial_group,
+        partial_flatten,
+        partial_default
+    )
+)]
+pub fn derive_partial(input: TokenStream) -> TokenStream {
+    let (input, field_values) = split_field_defaults(input.into());
+    let ast = match syn::parse2::<DeriveInput>(input) {
+        Ok(ast) => ast,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    expand(&ast, field_values).into()
+}
+
+/// Declares a struct and generates its partials, for definitions that cannot
+/// carry `#[derive(Partial)]` themselves (such as a local mirror of a struct
+/// from another crate).
+///
+/// The input is a struct definition with the same `#[partial(...)]` and field
+/// attributes the derive accepts. The struct is emitted without them,
+/// followed by everything the derive would generate for it.
+#[proc_macro]
+pub fn partial_for(input: TokenStream) -> TokenStream {
+    let (input, field_values) = split_field_defaults(input.into());
+    let mut ast = match syn::parse2::<DeriveInput>(input) {
+        Ok(ast) => ast,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let generated = expand(&ast, field_values.clone());
+    // Without the derive, nothing registers the helper attributes, so they
+    // must not reach the compiler.
+    ast.attrs.retain(|attr| {
+        !["partial", "partial_common", "omit"]
+            .iter()
+            .any(|name| attr.path().is_ident(name))
+    });
+    if let Data::Struct(data) = &mut ast.data {
+        for field in data.fields.iter_mut() {
+            field.attrs.retain(|attr| {
+                !FIELD_HELPER_ATTRS
+                    .iter()
+                    .any(|name| attr.path().is_ident(name))
+            });
+        }
+    }
+    let item = match &ast.data {
+        // Default field values go back after the type they were taken from.
+        Data::Struct(data) if !field_values.is_empty() => {
+            let DeriveInput {
+                attrs,
+                vis,
+                ident,
+                generics,
+                ..
+            } = &ast;
+            let where_clause = &generics.where_clause;
+            let fields = data.fields.iter().map(|field| {
+                let Field {
+                    attrs,
+                    vis,
+                    ident,
+                    ty,
+                    ..
+                } = field;
+                let value = ident
+                    .as_ref()
+                    .and_then(|ident| field_values.get(&ident.unraw().to_string()))
+                    .map(|value| quote! { = #value });
+                quote! { #(#attrs)* #vis #ident: #ty #value }
+            });
+            quote! {
+                #(#attrs)*
+                #vis struct #ident #generics #where_clause {
+                    #(#fields,)*
+                }
+            }
+        }
+        _ => ast.to_token_stream(),
+    };
+    quote! {
+        #item
+
+        #generated
+    }
+    .into()
+}
+
+/// Expands `#[derive(Partial)]` for an already parsed struct, given the
+/// default field values [`split_field_defaults`] took out of it.
+fn expand(
+    ast: &DeriveInput,
+    field_values: std::collections::HashMap<String, TokenStream2>,
+) -> TokenStream2 {
+    let orig_name = &ast.ident;
+
+    // --- MODIFIED: Collect #[partial] attributes, handling errors ---
+    let mut partial_args_list: Vec<PartialArgs> = Vec::new();
+    let mut first_error: Option<syn::Error> = None;
+
+    for attr in ast
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("partial"))
+    {
+        match attr.parse_args::<PartialArgs>() {
+            Ok(mut args) => {
+                args.attr_span = Some(attr.span());
+                if first_error.is_none() {
+                    // Only collect args if no error has occurred yet
+                    partial_args_list.push(args);
+                }
+            }
+            Err(err) => {
+                // Store the first error encountered
+                if first_error.is_none() {
+                    first_error = Some(err);
+                } else {
+                    // Optional: Combine errors if multiple attributes are invalid
+                    // first_error.as_mut().unwrap().combine(err);
+                }
+            }
+        }
+    }
+
+    // If any attribute failed to parse, return the error
+    if let Some(err) = first_error {
+        return err.to_compile_error();
+    }
+
+    // If no *valid* #[partial] attributes were found, provide the default one.
+    // This check happens *after* error handling.
+    if partial_args_list.is_empty() && !ast.attrs.iter().any(|attr| attr.path().is_ident("partial"))
+    {
+        // Add default only if no #[partial] attribute was present at all
+        partial_args_list.push(PartialArgs::default());
+    } else if partial_args_list.is_empty()
+        && ast.attrs.iter().any(|attr| attr.path().is_ident("partial"))
+    {
+        // If attributes were present but all were invalid (and errors handled above),
+        // we might want to return an empty TokenStream or a specific error.
+        // Since the first parse error is already returned, this case might not be strictly needed,
+        // but it's here for clarity. Let's return empty.
+        return TokenStream2::new();
+    }
+    // --- END MODIFICATION ---
+
+    let mut common = CommonArgs::default();
+    for attr in ast
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("partial_common"))
+    {
+        match attr.parse_args::<CommonArgs>() {
+            Ok(args) => {
+                common.omit_fields.extend(args.omit_fields);
+                common.optional_fields.extend(args.optional_fields);
+            }
+            Err(err) => return err.to_compile_error(),
+        }
+    }
+    for partial_args in &mut partial_args_list {
+        common.merge_into(partial_args);
+    }
+
+    let info = match StructInfo::new(ast, field_values) {
+        Ok(info) => info,
+        Err(err) => return err.to_compile_error(),
+    };
+    let field_args = &info.field_args;
+    let named_fields = &info.named_fields;
+
+    // Two attributes producing the same struct name would emit conflicting
+    // items; report every repeat at the attribute that introduced it.
+    let mut seen_targets = HashSet::new();
+    let duplicate_targets = partial_args_list
+        .iter()
+        .filter_map(|partial_args| {
+            let (name, span) = match &partial_args.target_name {
+                Some(lit) => (lit.value(), lit.span()),
+                None => (
+                    format!("Partial{}", orig_name.unraw()),
+                    partial_args.attr_span.unwrap_or_else(Span::call_site),
+                ),
+            };
+            (!seen_targets.insert(name.clone())).then(|| {
+                syn::Error::new(
+                    span,
+                    format!(
+                        "a partial named `{}` is already generated for `{}`",
+                        name,
+                        orig_name.unraw()
+                    ),
+                )
+            })
+        })
+        .reduce(|mut combined, err| {
+            combined.combine(err);
+            combined
+        });
+    if let Some(err) = duplicate_targets {
+        return err.to_compile_error();
+    }
+
+    // Every `hash_helper` generates the same `partial_hash` method on the
+    // original struct, so only one partial may ask for it.
+    let repeated_hash_helpers = partial_args_list
+        .iter()
+        .filter(|partial_args| partial_args.hash_helper)
+        .skip(1)
+        .map(|partial_args| {
+            syn::Error::new(
+                partial_args.attr_span.unwrap_or_else(Span::call_site),
+                format!(
+                    "'hash_helper' is already given on another partial of `{}`",
+                    orig_name.unraw()
+                ),
+            )
+        })
+        .reduce(|mut combined, err| {
+            combined.combine(err);
+            combined
+        });
+    if let Some(err) = repeated_hash_helpers {
+        return err.to_compile_error();
+    }
+
+    // `#[partial_group(creation)]` refers to the partial whose name is
+    // `creation` in snake_case, so a group nothing answers to is a typo.
+    let group_names: HashSet<String> = seen_targets
+        .iter()
+        .map(|name| name.to_snake_case())
+        .collect();
+    let unknown_groups = named_fields
+        .iter()
+        .filter_map(|(_, _, name)| field_args.get(name))
+        .flat_map(|args| args.groups.iter())
+        .filter(|group| !group_names.contains(&group.unraw().to_string()))
+        .map(|group| {
+            syn::Error::new(
+                group.span(),
+                format!(
+                    "no partial of `{}` is named `{}`; name one `{}` to use this group",
+                    orig_name.unraw(),
+                    group.unraw(),
+                    group.unraw().to_string().to_upper_camel_case()
+                ),
+            )
+        })
+        .reduce(|mut combined, err| {
+            combined.combine(err);
+            combined
+        });
+    if let Some(err) = unknown_groups {
+        return err.to_compile_error();
+    }
+
+    // Generate code for each partial struct configuration.
+    let generated: Vec<_> = partial_args_list
+        .into_iter()
+        .map(|partial_args| generate_partial(&info, partial_args))
+        .collect();
+
+    // Every partial converts into each sibling that implements `From` the
+    // full struct, by rebuilding the full struct in between.
+    let routes: Vec<_> = generated
+        .iter()
+        .filter_map(|partial| partial.as_ref().ok())
+        .map(|partial| &partial.route)
+        .collect();
+    let conversions: Vec<_> = routes
+        .iter()
+        .map(|from| {
+            let to: Vec<_> = routes
+                .iter()
+                .copied()
+                .filter(|to| to.from_full && to.name != from.name)
+                .collect();
+            partial_conversions(ast, from, &to)
+        })
+        .collect();
+    let partial_structs = generated.into_iter().map(|partial| match partial {
+        Ok(partial) => partial.tokens,
+        Err(err) => err.to_compile_error(),
+    });
+
+    // Combine the generated code for all partial structs
+    quote! {
+        #(#partial_structs)*
+
+        #(#conversions)*
+    }
+}
+
+/// What every partial of one struct shares: its fields and their
+/// field-level attributes, checked once before any partial is generated.
+struct StructInfo<'a> {
+    ast: &'a DeriveInput,
+    fields: &'a Punctuated<Field, Token![,]>,
+    field_args: std::collections::HashMap<String, FieldArgs>,
+    /// Each field with its ident and unraw name, in declaration order.
+    named_fields: Vec<(&'a Field, &'a Ident, String)>,
+    /// The field each `#[partial_flatten]` field was inlined from.
+    flatten_parent: std::collections::HashMap<String, &'a Ident>,
+    /// Default field values (`name: Type = expr`), by field name.
+    field_values: std::collections::HashMap<String, TokenStream2>,
+    has_deprecated_fields: bool,
+}
+
+impl<'a> StructInfo<'a> {
+    fn new(
+        ast: &'a DeriveInput,
+        field_values: std::collections::HashMap<String, TokenStream2>,
+    ) -> syn::Result<Self> {
+        let orig_name = &ast.ident;
+
+        // Ensure the input is a struct with named fields.
+        let fields = match &ast.data {
+            Data::Struct(data) => match &data.fields {
+                Fields::Named(named) => &named.named,
+                // --- FIXED: Use data.fields for span ---
+                Fields::Unnamed(fields_unnamed) => {
+                    return Err(syn::Error::new_spanned(
+                        fields_unnamed, // Span over the unnamed fields ()
+                        "Partial can only be derived for structs with named fields",
+                    ));
+                }
+                Fields::Unit => {
+                    return Err(syn::Error::new_spanned(
+                        data.struct_token, // Span over the `struct` keyword
+                        "Partial cannot be derived for unit structs",
+                    ));
+                } // --- END FIX ---
+            },
+            Data::Enum(data_enum) => {
+                return Err(syn::Error::new_spanned(
+                    data_enum.enum_token, // Span over the `enum` keyword
+                    "Partial can only be derived for structs, not enums",
+                ));
+            }
+            Data::Union(data_union) => {
+                return Err(syn::Error::new_spanned(
+                    data_union.union_token, // Span over the `union` keyword
+                    "Partial can only be derived for structs, not unions",
+                ));
+            }
+        };
+
+        let mut field_args = std::collections::HashMap::new();
+        for field in fields.iter() {
+            let args = FieldArgs::from_field(field)?;
+            if let Some(ident) = &field.ident {
+                field_args.insert(ident.unraw().to_string(), args);
+            }
+        }
+
+        // Every partial looks fields up by their unraw name; compute it once.
+        let named_fields: Vec<(&Field, &Ident, String)> = fields
+            .iter()
+            .filter_map(|field| {
+                let ident = field.ident.as_ref()?;
+                Some((field, ident, ident.unraw().to_string()))
+            })
+            .collect();
+        let field_names: HashSet<&str> = named_fields
+            .iter()
+            .map(|(_, _, name)| name.as_str())
+            .collect();
+
+        // Flattened fields sit next to the outer fields in every partial, so a
+        // name may only be used once across both.
+        let flatten_of = |name: &str| field_args.get(name).and_then(|args| args.flatten.as_ref());
+        let mut flatten_parent = std::collections::HashMap::new();
+        let mut flatten_errors = Vec::new();
+        for (_, parent, parent_name) in &named_fields {
+            let Some(flatten) = flatten_of(parent_name) else {
+                continue;
+            };
+            for inner in flatten
+                .fields
+                .iter()
+                .filter_map(|field| field.ident.as_ref())
+            {
+                let inner_name = inner.unraw().to_string();
+                if field_names.contains(inner_name.as_str())
+                    || flatten_parent.contains_key(&inner_name)
+                {
+                    flatten_errors.push(syn::Error::new(
+                        inner.span(),
+                        format!(
+                            "flattened field `{}` of `{}` collides with another field of `{}`",
+                            inner_name,
+                            parent_name,
+                            orig_name.unraw()
+                        ),
+                    ));
+                } else {
+                    flatten_parent.insert(inner_name, *parent);
+                }
+            }
+        }
+        if let Some(err) = flatten_errors.into_iter().reduce(|mut combined, err| {
+            combined.combine(err);
+            combined
+        }) {
+            return Err(err);
+        }
+        let has_deprecated_fields = fields
+            .iter()
+            .flat_map(|field| field.attrs.iter())
+            .any(|attr| attr.path().is_ident("deprecated"));
+
+        Ok(StructInfo {
+            ast,
+            fields,
+            field_args,
+            named_fields,
+            flatten_parent,
+            field_values,
+            has_deprecated_fields,
+        })
+    }
+}
+
+/// Generates one partial of the struct described by `info`: the partial
+/// struct, its companion types and every impl `partial_args` asks for.
+fn generate_partial(info: &StructInfo, partial_args: PartialArgs) -> syn::Result<GeneratedPartial> {
+    let ast = info.ast;
+    let orig_name = &ast.ident;
+    let fields = info.fields;
+    let field_args = &info.field_args;
+    let named_fields = &info.named_fields;
+    let field_names: HashSet<&str> = named_fields
+        .iter()
+        .map(|(_, _, name)| name.as_str())
+        .collect();
+    let has_deprecated_fields = info.has_deprecated_fields;
+    let is_sort_key = |name: &str| field_args.get(name).is_some_and(|args| args.sort_key);
+    let flatten_of = |name: &str| field_args.get(name).and_then(|args| args.flatten.as_ref());
+
+    // Where a partial field lives in the full struct, relative to a value of it.
+    let source_of = |field: &Field| {
+        let ident = &field.ident;
+        let parent = ident
+            .as_ref()
+            .and_then(|ident| info.flatten_parent.get(&ident.unraw().to_string()));
+        match parent {
+            Some(parent) => quote! { #parent.#ident },
+            None => quote! { #ident },
+        }
+    };
+
+    let target_name_str = partial_args
+        .target_name
+        .as_ref()
+        .map(|lit| lit.value())
+        .unwrap_or_else(|| format!("Partial{}", orig_name.unraw()));
+    // Generated names take the call-site span rather than the struct
+    // name's, so they resolve where the derive is expanded even when the
+    // struct comes out of a `macro_rules!`.
+    let target_ident = Ident::new(&target_name_str, Span::call_site());
+    // Generated types are as visible as the original struct unless
+    // `vis = "..."` says otherwise.
+    let vis = partial_args.vis.as_ref().unwrap_or(&ast.vis);
+    let vis = match &partial_args.module {
+        Some(_) => vis_in_module(vis),
+        None => vis.clone(),
+    };
+    // With `allow_dead_code`, the generated types carry the attribute and
+    // the impls are wrapped in a `const _` block that carries it.
+    let dead_code_attr = if partial_args.allow_dead_code {
+        quote! { #[allow(dead_code)] }
+    } else {
+        quote! {}
+    };
+
+    // `where_bound(...)` predicates join the full struct's own where clause
+    // wherever the full struct is involved.
+    let bounded_generics = {
+        let mut generics = ast.generics.clone();
+        if !partial_args.where_bounds.is_empty() {
+            generics
+                .make_where_clause()
+                .predicates
+                .extend(partial_args.where_bounds.iter().cloned());
+        }
+        generics
+    };
+    let (orig_impl_generics, orig_ty_generics, orig_where_clause) =
+        bounded_generics.split_for_impl();
+    let orig_predicates: Vec<_> = bounded_generics
+        .where_clause
+        .iter()
+        .flat_map(|where_clause| where_clause.predicates.iter())
+        .collect();
+
+    // Fields tagged with groups are left out of every other partial.
+    let target_group = target_name_str.to_snake_case();
+    let omit_names: HashSet<String> = partial_args
+        .omit_fields
+        .iter()
+        .map(|id| id.unraw().to_string())
+        .chain(
+            named_fields
+                .iter()
+                .filter(|(field, ..)| {
+                    partial_args
+                        .omit_types
+                        .iter()
+                        .any(|listed| type_matches(&field.ty, listed))
+                })
+                .map(|(_, _, name)| name.clone()),
+        )
+        .chain(
+            named_fields
+                .iter()
+                .map(|(_, _, name)| name)
+                .filter(|name| {
+                    field_args.get(*name).is_some_and(|args| {
+                        !args.groups.is_empty()
+                            && !args
+                                .groups
+                                .iter()
+                                .any(|group| group.unraw() == target_group)
+                    })
+                })
+                .cloned(),
+        )
+        .collect();
+
+    let optional_names: HashSet<String> = if partial_args.all_optional {
+        named_fields
+            .iter()
+            .map(|(_, _, name)| name)
+            .filter(|name| !omit_names.contains(*name))
+            .cloned()
+            .collect()
+    } else {
+        // A bare entry for a field that is already an `Option` keeps that
+        // `Option` as its optional form instead of nesting another one, so
+        // the field is carried over like an included one.
+        let already_optional: HashSet<&str> = named_fields
+            .iter()
+            .filter(|(field, ..)| is_option(&field.ty))
+            .map(|(_, _, name)| name.as_str())
+            .collect();
+        partial_args
+            .optional_fields
+            .iter()
+            .filter(|optional| {
+                optional.via.is_some()
+                    || optional.default.is_some()
+                    || !already_optional.contains(optional.ident.unraw().to_string().as_str())
+            })
+            .map(|optional| optional.ident.unraw().to_string())
+            .collect()
+    };
+
+    // Typos in `omit(...)`/`optional(...)` would otherwise be ignored and
+    // leave the field in the partial, so reject names that don't exist.
+    let unknown_fields = partial_args
+        .omit_fields
+        .iter()
+        .chain(
+            partial_args
+                .optional_fields
+                .iter()
+                .map(|optional| &optional.ident),
+        )
+        .chain(partial_args.renames.iter().map(|(field, _)| field))
+        .chain(partial_args.redact_fields.iter())
+        .chain(partial_args.omitted_defaults.iter().map(|(field, _)| field))
+        .filter(|ident| !field_names.contains(ident.unraw().to_string().as_str()))
+        .map(|ident| {
+            syn::Error::new(
+                ident.span(),
+                format!(
+                    "field `{}` does not exist on `{}`",
+                    ident.unraw(),
+                    orig_name.unraw()
+                ),
+            )
+        })
+        .reduce(|mut combined, err| {
+            combined.combine(err);
+            combined
+        });
+    if let Some(err) = unknown_fields {
+        return Err(err);
+    }
+
+    // A field listed in both `omit` and `optional` has no meaningful shape,
+    // so report each occurrence at its identifier inside `optional(...)`.
+    let conflicts = partial_args
+        .optional_fields
+        .iter()
+        .map(|optional| &optional.ident)
+        .filter(|ident| omit_names.contains(&ident.unraw().to_string()))
+        .map(|ident| {
+            syn::Error::new(
+                ident.span(),
+                format!("field `{}` cannot be both omitted and optional; `omit` and `optional` are mutually exclusive", ident),
+            )
+        })
+        .reduce(|mut combined, err| {
+            combined.combine(err);
+            combined
+        });
+    if let Some(err) = conflicts {
+        return Err(err);
+    }
+
+    // `redact(...)` only shapes the omitted-fields struct's `Debug`, and
+    // `default(...)` only fills omitted fields.
+    let redact_errors = partial_args
+        .redact_fields
+        .iter()
+        .filter(|ident| !omit_names.contains(&ident.unraw().to_string()))
+        .map(|ident| {
+            syn::Error::new(
+                ident.span(),
+                format!("field `{}` is not omitted from `{}`; only omitted fields can be redacted", ident.unraw(), target_ident),
+            )
+        })
+        .chain(
+            partial_args
+                .omitted_defaults
+                .iter()
+                .map(|(ident, _)| ident)
+                .filter(|ident| !omit_names.contains(&ident.unraw().to_string()))
+                .map(|ident| {
+                    syn::Error::new(
+                        ident.span(),
+                        format!("field `{}` is not omitted from `{}`; `default(...)` only applies to omitted fields", ident.unraw(), target_ident),
+                    )
+                }),
+        )
+        .reduce(|mut combined, err| {
+            combined.combine(err);
+            combined
+        });
+    if let Some(err) = redact_errors {
+        return Err(err);
+    }
+
+    // `rename(...)` only changes the name inside this partial, so only
+    // kept, non-flattened fields can be renamed, and the new names must
+    // not clash with the other fields of the partial.
+    let renamed_to: std::collections::HashMap<String, &Ident> = partial_args
+        .renames
+        .iter()
+        .map(|(field, new_name)| (field.unraw().to_string(), new_name))
+        .collect();
+    let partial_name = |name: &str| {
+        renamed_to
+            .get(name)
+            .map_or_else(|| name.to_string(), |new_name| new_name.unraw().to_string())
+    };
+    let mut renamed_names = HashSet::new();
+    let rename_errors = partial_args
+        .renames
+        .iter()
+        .filter_map(|(field, new_name)| {
+            let name = field.unraw().to_string();
+            if omit_names.contains(&name) {
+                Some(syn::Error::new(
+                    field.span(),
+                    format!(
+                        "field `{}` is omitted from `{}` and cannot be renamed",
+                        name, target_ident
+                    ),
+                ))
+            } else if flatten_of(&name).is_some() {
+                Some(syn::Error::new(
+                    field.span(),
+                    format!(
+                        "field `{}` is flattened with `#[partial_flatten]` and cannot be renamed",
+                        name
+                    ),
+                ))
+            } else {
+                let new_name = new_name.unraw().to_string();
+                let clashes = named_fields.iter().any(|(_, _, other)| {
+                    *other != name && (*other == new_name || partial_name(other) == new_name)
+                });
+                clashes.then(|| {
+                    syn::Error::new(
+                        field.span(),
+                        format!(
+                            "`{}` already names another field of `{}`",
+                            new_name, orig_name
+                        ),
+                    )
+                })
+            }
+        })
+        .chain(
+            partial_args
+                .renames
+                .iter()
+                .filter(|(field, _)| !renamed_names.insert(field.unraw().to_string()))
+                .map(|(field, _)| {
+                    syn::Error::new(
+                        field.span(),
+                        format!("field `{}` is renamed twice", field.unraw()),
+                    )
+                }),
+        )
+        .reduce(|mut combined, err| {
+            combined.combine(err);
+            combined
+        });
+    if let Some(err) = rename_errors {
+        return Err(err);
+    }
+    let renamed_fields: std::collections::HashMap<&str, Field> = named_fields
+        .iter()
+        .filter_map(|(field, _, name)| {
+            let new_name = renamed_to.get(name.as_str())?;
+            let mut renamed = (*field).clone();
+            renamed.ident = Some((*new_name).clone());
+            Some((name.as_str(), renamed))
+        })
+        .collect();
+    // Where a kept field lives in the full struct; renamed fields map
+    // back to their original name.
+    let renamed_from: std::collections::HashMap<String, &Ident> = named_fields
+        .iter()
+        .filter(|(_, _, name)| renamed_to.contains_key(name.as_str()))
+        .map(|(_, ident, name)| (partial_name(name), *ident))
+        .collect();
+    let source_of = |field: &Field| {
+        let original = field
+            .ident
+            .as_ref()
+            .and_then(|ident| renamed_from.get(&ident.unraw().to_string()));
+        match original {
+            Some(original) => quote! { #original },
+            None => source_of(field),
+        }
+    };
+
+    // Classify each field once, in declaration order; everything below
+    // works from this list instead of looking names up again. Renamed
+    // fields appear under their new name; the `Ident` and name stay the
+    // original ones.
+    let field_kinds: Vec<(&Field, &Ident, &str, FieldKind)> = named_fields
+        .iter()
+        .map(|(field, ident, name)| {
+            let kind = if omit_names.contains(name) {
+                FieldKind::Omitted
+            } else if optional_names.contains(name) {
+                FieldKind::Optional
+            } else {
+                FieldKind::Included
+            };
+            (
+                renamed_fields.get(name.as_str()).unwrap_or(field),
+                *ident,
+                name.as_str(),
+                kind,
+            )
+        })
+        .collect();
+    let fields_of = |wanted: FieldKind| -> Vec<&Field> {
+        field_kinds
+            .iter()
+            .filter(|(.., kind)| *kind == wanted)
+            .map(|(field, ..)| *field)
+            .collect()
+    };
+    // Flattened fields are replaced by the embedded struct's fields.
+    let included_fields: Vec<&Field> = field_kinds
+        .iter()
+        .filter(|(.., kind)| *kind == FieldKind::Included)
+        .flat_map(|&(field, _, name, _)| match flatten_of(name) {
+            Some(flatten) => flatten.fields.iter().collect(),
+            None => vec![field],
+        })
+        .collect();
+    let omitted_fields = fields_of(FieldKind::Omitted);
+    let optional_fields = fields_of(FieldKind::Optional);
+    let optional_flattened = optional_fields
+        .iter()
+        .filter_map(|field| field.ident.as_ref())
+        .filter(|ident| flatten_of(&ident.unraw().to_string()).is_some())
+        .map(|ident| {
+            syn::Error::new(
+                ident.span(),
+                format!("field `{}` is flattened with `#[partial_flatten]` and cannot be optional in `{}`", ident.unraw(), target_ident),
+            )
+        })
+        .reduce(|mut combined, err| {
+            combined.combine(err);
+            combined
+        });
+    if let Some(err) = optional_flattened {
+        return Err(err);
+    }
+    // A partial without fields carries no data, which is almost always an
+    // over-eager `omit(...)`.
+    if included_fields.is_empty() && optional_fields.is_empty() && !partial_args.allow_empty {
+        let span = partial_args
+            .target_name
+            .as_ref()
+            .map(|lit| lit.span())
+            .or(partial_args.attr_span)
+            .unwrap_or_else(Span::call_site);
+        return Err(syn::Error::new(
+            span,
+            format!("`{}` keeps no fields of `{}`; omit fewer fields, or add `allow_empty` if this is intended", target_ident, orig_name.unraw()),
+        ));
+    }
+
+    // Each generated struct only carries the generic parameters its own
+    // fields use; parameters that only appear in omitted fields move onto
+    // the methods that take or return those fields.
+    let partial_generics = generics_for_fields(
+        &ast.generics,
+        &included_fields
+            .iter()
+            .chain(optional_fields.iter())
+            .copied()
+            .collect::<Vec<_>>(),
+    );
+    let (partial_impl_generics, partial_ty_generics, partial_where_clause) =
+        partial_generics.split_for_impl();
+    let partial_predicates: Vec<_> = partial_generics
+        .where_clause
+        .iter()
+        .flat_map(|where_clause| where_clause.predicates.iter())
+        .collect();
+    let omitted_generics = generics_for_fields(&ast.generics, &omitted_fields);
+    let (omitted_impl_generics, omitted_ty_generics, omitted_where_clause) =
+        omitted_generics.split_for_impl();
+    let method_params = extra_method_params(&ast.generics, &partial_generics);
+    let method_generics = if method_params.is_empty() {
+        quote! {}
+    } else {
+        quote! { <#(#method_params),*> }
+    };
+
+    // --- Field attribute copying remains the same ---
+    let forward_attrs = partial_args.forward_attrs.as_deref();
+    // With `wrap_all(Wrapper)`, included fields are stored as `Wrapper<T>`:
+    // values coming from the full struct are wrapped with `From`, and
+    // values going back are unwrapped with `FieldWrapper::into_inner`.
+    let wrap_all = partial_args.wrap_all.clone();
+    let included_ty = |field: &Field| {
+        let ty = &field.ty;
+        match &wrap_all {
+            Some(wrapper) => quote! { #wrapper<#ty> },
+            None => quote! { #ty },
+        }
+    };
+    let wrap_included = |value: TokenStream2| match &wrap_all {
+        Some(_) => quote! { ::core::convert::Into::into(#value) },
+        None => value,
+    };
+    let unwrap_included = |value: TokenStream2| match &wrap_all {
+        Some(_) => quote! { ::partial_struct::FieldWrapper::into_inner(#value) },
+        None => value,
+    };
+    let borrow_included = |place: TokenStream2| match &wrap_all {
+        Some(_) => quote! { ::partial_struct::FieldWrapper::inner(&#place) },
+        None => quote! { &#place },
+    };
+    // The wrapped value as a place expression, for comparisons.
+    let read_included = |place: TokenStream2| match &wrap_all {
+        Some(_) => quote! { *::partial_struct::FieldWrapper::inner(&#place) },
+        None => place,
+    };
+    // Default field values carry over to included fields stored as is.
+    let field_values: std::collections::HashMap<String, &TokenStream2> = info
+        .field_values
+        .iter()
+        .map(|(name, value)| (partial_name(name), value))
+        .collect();
+    let included_fields_tokens = included_fields.iter().map(|field| {
+        let ident = &field.ident;
+        let ty = included_ty(field);
+        let attrs = forwarded_attrs(field, forward_attrs);
+        let value = ident
+            .as_ref()
+            .and_then(|ident| field_values.get(&ident.unraw().to_string()))
+            .filter(|_| wrap_all.is_none())
+            .map(|value| quote! { = #value });
+        quote! {
+            #(#attrs)*
+            pub #ident: #ty #value
+        }
+    });
+    // ---
+
+    // Optional fields are stored as `Option<T>` unless given a wrapper with `via`.
+    let optional_specs: std::collections::HashMap<String, &OptionalField> = partial_args
+        .optional_fields
+        .iter()
+        .map(|optional| (partial_name(&optional.ident.unraw().to_string()), optional))
+        .collect();
+    let repr_of = |field| OptionalRepr::of(field, &optional_specs);
+    // With a derived `Serialize`, `None` optionals are left out of the
+    // output instead of written as `null`. A `Serialize` derived through
+    // `cfg_derive` gets the attribute behind the same predicate.
+    let is_serialize = |path: &syn::Path| {
+        path.segments
+            .last()
+            .is_some_and(|last| last.ident == "Serialize")
+    };
+    let skip_none_attr = if partial_args.serialize_none {
+        None
+    } else if partial_args.derive_traits.iter().any(is_serialize) {
+        Some(quote! { #[serde(skip_serializing_if = "::core::option::Option::is_none")] })
+    } else {
+        partial_args
+            .cfg_derives
+            .iter()
+            .find(|(_, traits)| traits.iter().any(is_serialize))
+            .map(|(predicate, _)| quote! { #[cfg_attr(#predicate, serde(skip_serializing_if = "::core::option::Option::is_none"))] })
+    };
+    let optional_fields_tokens = optional_fields.iter().map(|field| {
+        let ident = &field.ident;
+        let repr = repr_of(field);
+        let field_ty = repr.field_ty();
+        let attrs: Vec<_> = forwarded_attrs(field, forward_attrs).collect();
+        // `via` wrappers have no `is_none`, and a forwarded attribute may
+        // already say when to skip the field.
+        let skips_already = attrs.iter().any(|attr| {
+            attr.path().is_ident("serde")
+                && attr
+                    .meta
+                    .to_token_stream()
+                    .to_string()
+                    .contains("skip_serializing_if")
+        });
+        let skip_none = skip_none_attr
+            .as_ref()
+            .filter(|_| repr.via.is_none() && !skips_already);
+        quote! {
+            #(#attrs)*
+            #skip_none
+            pub #ident: #field_ty
+        }
+    });
+    // ---
+
+    // Optional fields declared with a default are rebuilt from it, so only
+    // the others take a fallback parameter.
+    let optional_defaults: std::collections::HashMap<String, &syn::Expr> = partial_args
+        .optional_fields
+        .iter()
+        .filter_map(|optional| {
+            Some((
+                partial_name(&optional.ident.unraw().to_string()),
+                optional.default.as_ref()?,
+            ))
+        })
+        .collect();
+    let default_for = |name: &str| optional_defaults.get(name).copied();
+    let default_of = |field: &Field| {
+        field
+            .ident
+            .as_ref()
+            .and_then(|ident| default_for(&ident.unraw().to_string()))
+    };
+    let fallback_fields: Vec<_> = optional_fields
+        .iter()
+        .copied()
+        .filter(|field| default_of(field).is_none())
+        .collect();
+
+    // Fallbacks for `Box` fields take the owned value and box it, so
+    // callers don't build the box themselves.
+    let fallback_param = |field: &&Field| {
+        let ident = &field.ident;
+        match boxed_inner(&field.ty) {
+            Some(inner) => quote! { #ident: Option<#inner> },
+            None => {
+                let ty = &field.ty;
+                quote! { #ident: Option<#ty> }
+            }
+        }
+    };
+    let optional_fallback_params: Vec<_> = fallback_fields.iter().map(fallback_param).collect();
+    // `complete(...)` only makes sense when some field can be missing,
+    // and must not shadow the `PartialComplete` method of other partials.
+    if partial_args.freeze_optional && fallback_fields.is_empty() {
+        let span = partial_args
+            .target_name
+            .as_ref()
+            .map(|lit| lit.span())
+            .or(partial_args.attr_span)
+            .unwrap_or_else(Span::call_site);
+        return Err(syn::Error::new(
+            span,
+            format!(
+                "`freeze_optional` on `{}` needs an optional field without a default",
+                target_ident
+            ),
+        ));
+    }
+    let to_method_params: Vec<_> = omitted_fields
+        .iter()
+        .map(|field| {
+            let ident = &field.ident;
+            let ty = &field.ty;
+            if partial_args.into_params {
+                quote! { #ident: impl ::core::convert::Into<#ty> }
+            } else {
+                quote! { #ident: #ty }
+            }
+        })
+        .chain(fallback_fields.iter().map(fallback_param))
+        .collect();
+
+    // Construct fields in the order they appear in the original struct.
+    // One pass fills the consuming, cloning and `_or_current` bodies.
+    let mut construction_assignments = TokenStream2::new();
+    let mut cloned_construction_assignments = TokenStream2::new();
+    let mut or_current_assignments = TokenStream2::new();
+    for &(field, ident, name, kind) in &field_kinds {
+        // `ident` names the field in the full struct, `partial_ident` in
+        // the partial (they differ for renamed fields).
+        let partial_ident = &field.ident;
+        let repr = repr_of(field);
+        let taken = repr.take(quote! { self.#partial_ident });
+        let cloned = repr.take(quote! { self.#partial_ident.clone() });
+        match (kind, default_of(field)) {
+            (FieldKind::Omitted, _) => {
+                // Omitted fields come from the parameters (no clone needed)
+                let value = if partial_args.into_params {
+                    quote! { ::core::convert::Into::into(#ident) }
+                } else {
+                    quote! { #ident }
+                };
+                construction_assignments.extend(quote! { #ident: #value, });
+                cloned_construction_assignments.extend(quote! { #ident: #value, });
+                or_current_assignments.extend(quote! { #ident: current.#ident, });
+            }
+            (FieldKind::Optional, Some(default)) => {
+                // Optional fields with a default use it if self holds None
+                construction_assignments
+                    .extend(quote! { #ident: #taken.unwrap_or_else(|| #default), });
+                cloned_construction_assignments
+                    .extend(quote! { #ident: #cloned.unwrap_or_else(|| #default), });
+                or_current_assignments.extend(quote! { #ident: #taken.unwrap_or(current.#ident), });
+            }
+            (FieldKind::Optional, None) => {
+                // Other optional fields fall back to the parameter of the same name
+                let fallback = match boxed_inner(&field.ty) {
+                    Some(_) => quote! { #partial_ident.map(::core::convert::Into::into) },
+                    None => quote! { #partial_ident },
+                };
+                construction_assignments.extend(quote! {
+                    #ident: #taken.or(#fallback).expect("Optional field must be provided"),
+                });
+                cloned_construction_assignments.extend(quote! {
+                    #ident: #cloned.or(#fallback).expect("Optional field must be provided"),
+                });
+                or_current_assignments.extend(quote! { #ident: #taken.unwrap_or(current.#ident), });
+            }
+            (FieldKind::Included, _) => match flatten_of(name) {
+                Some(flatten) => {
+                    let moved = flatten.rebuild(|inner| unwrap_included(quote! { self.#inner }));
+                    let cloned =
+                        flatten.rebuild(|inner| unwrap_included(quote! { self.#inner.clone() }));
+                    construction_assignments.extend(quote! { #ident: #moved, });
+                    cloned_construction_assignments.extend(quote! { #ident: #cloned, });
+                    or_current_assignments.extend(quote! { #ident: #moved, });
+                }
+                None => {
+                    let moved = unwrap_included(quote! { self.#partial_ident });
+                    let cloned = unwrap_included(quote! { self.#partial_ident.clone() });
+                    construction_assignments.extend(quote! { #ident: #moved, });
+                    cloned_construction_assignments.extend(quote! { #ident: #cloned, });
+                    or_current_assignments.extend(quote! { #ident: #moved, });
+                }
+            },
+        }
+    }
+
+    let included_field_types = included_fields.iter().map(|field| included_ty(field));
+    let optional_field_types: Vec<_> = optional_fields
+        .iter()
+        .map(|field| repr_of(field).field_ty())
+        .collect();
+
+    // Fields marked `#[partial(sort_key)]` replace the derived ordering
+    // with one that compares those fields first, in declaration order.
+    // A flattened field is compared through the fields it inlines.
+    let sort_keys: Vec<_> = field_kinds
+        .iter()
+        .filter(|&&(_, _, name, kind)| kind != FieldKind::Omitted && is_sort_key(name))
+        .flat_map(|&(field, _, name, kind)| match flatten_of(name) {
+            Some(flatten) => flatten.fields.iter().map(|inner| (inner, kind)).collect(),
+            None => vec![(field, kind)],
+        })
+        .collect();
+    // The other kept fields break ties as stored, so only values the
+    // derived `PartialEq` calls equal compare `Equal`. Wrapped sort keys
+    // are compared again as stored, since the wrapper may hold more state.
+    let tiebreaks: Vec<_> = field_kinds
+        .iter()
+        .filter(|&&(_, _, name, kind)| {
+            kind != FieldKind::Omitted
+                && (!is_sort_key(name) || (kind == FieldKind::Included && wrap_all.is_some()))
+        })
+        .flat_map(|&(field, _, name, kind)| match flatten_of(name) {
+            Some(flatten) => flatten.fields.iter().map(|inner| (inner, kind)).collect(),
+            None => vec![(field, kind)],
+        })
+        .collect();
+    // Listing a trait twice in `derive(...)` would emit conflicting impls.
+    let mut derive_traits = partial_args.derive_traits;
+    let mut seen_derives = HashSet::new();
+    derive_traits.retain(|path| seen_derives.insert(path.to_token_stream().to_string()));
+
+    // Floats are the most common reason `derive(Eq)` fails on a partial;
+    // point at the offending field instead of leaving it to the derive.
+    if let Some(eq_ident) = derive_traits.iter().find(|path| is_std_derive(path, "Eq")) {
+        let float_fields = included_fields
+            .iter()
+            .chain(optional_fields.iter())
+            .filter(|field| {
+                let mut refs = GenericRefs::default();
+                refs.collect(field.ty.to_token_stream());
+                refs.idents.contains("f32") || refs.idents.contains("f64")
+            })
+            .map(|field| {
+                let name = field.ident.as_ref().map(|id| id.unraw().to_string()).unwrap_or_default();
+                syn::Error::new_spanned(
+                    eq_ident,
+                    format!(
+                        "`{}` derives `Eq`, but field `{}` contains a floating-point type, which does not implement `Eq`; remove `Eq` from `derive(...)` or omit `{}`",
+                        target_ident, name, name
+                    ),
+                )
+            })
+            .reduce(|mut combined, err| {
+                combined.combine(err);
+                combined
+            });
+        if let Some(err) = float_fields {
+            return Err(err);
+        }
+    }
+
+    let mut ordering_tokens = quote! {};
+    if !sort_keys.is_empty() {
+        let derives_ord = derive_traits.iter().any(|path| is_std_derive(path, "Ord"));
+        let derives_partial_ord = derive_traits
+            .iter()
+            .any(|path| is_std_derive(path, "PartialOrd"));
+        derive_traits
+            .retain(|path| !is_std_derive(path, "Ord") && !is_std_derive(path, "PartialOrd"));
+        let key_idents: Vec<_> = sort_keys.iter().map(|(field, _)| &field.ident).collect();
+        let key_types: Vec<_> = sort_keys.iter().map(|(field, _)| &field.ty).collect();
+        // Wrapped fields are ordered by the value they wrap.
+        let key_of = |receiver: TokenStream2| -> Vec<TokenStream2> {
+            sort_keys
+                .iter()
+                .map(|(field, kind)| {
+                    let ident = &field.ident;
+                    match kind {
+                        FieldKind::Included => borrow_included(quote! { #receiver.#ident }),
+                        _ => quote! { &#receiver.#ident },
+                    }
+                })
+                .collect()
+        };
+        let mut self_keys = key_of(quote! { self });
+        let mut other_keys = key_of(quote! { other });
+        let tiebreak_idents: Vec<_> = tiebreaks.iter().map(|(field, _)| &field.ident).collect();
+        self_keys.extend(tiebreak_idents.iter().map(|ident| quote! { &self.#ident }));
+        other_keys.extend(tiebreak_idents.iter().map(|ident| quote! { &other.#ident }));
+        let tiebreak_types: Vec<_> = tiebreaks
+            .iter()
+            .map(|&(field, kind)| match kind {
+                FieldKind::Included => included_ty(field),
+                _ => repr_of(field).field_ty(),
+            })
+            .collect();
+        let ordering_doc = format!(
+            "Orders by the sort key field(s): {}, then by the remaining fields in declaration order.",
+            key_idents
+                .iter()
+                .filter_map(|ident| ident.as_ref().map(|id| id.unraw().to_string()))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        let partial_cmp_body = if derives_ord {
+            quote! { Some(::core::cmp::Ord::cmp(self, other)) }
+        } else {
+            quote! {
+                #(
+                    match ::core::cmp::PartialOrd::partial_cmp(#self_keys, #other_keys) {
+                        Some(::core::cmp::Ordering::Equal) => {}
+                        ordering => return ordering,
+                    }
+                )*
+                Some(::core::cmp::Ordering::Equal)
+            }
+        };
+        if derives_ord {
+            ordering_tokens.extend(quote! {
+                #[doc = #ordering_doc]
+                impl #partial_impl_generics ::core::cmp::Ord for #target_ident #partial_ty_generics
+                where
+                    #( #partial_predicates, )*
+                    #( #key_types: ::core::cmp::Ord, )*
+                    #( #tiebreak_types: ::core::cmp::Ord, )*
+                {
+                    fn cmp(&self, other: &Self) -> ::core::cmp::Ordering {
+                        ::core::cmp::Ordering::Equal
+                            #( .then_with(|| ::core::cmp::Ord::cmp(#self_keys, #other_keys)) )*
+                    }
+                }
+            });
+        }
+        if derives_partial_ord {
+            let key_bound = if derives_ord {
+                quote! { ::core::cmp::Ord }
+            } else {
+                quote! { ::core::cmp::PartialOrd }
+            };
+            ordering_tokens.extend(quote! {
+                #[doc = #ordering_doc]
+                impl #partial_impl_generics ::core::cmp::PartialOrd for #target_ident #partial_ty_generics
+                where
+                    #( #partial_predicates, )*
+                    #( #key_types: #key_bound, )*
+                    #( #tiebreak_types: #key_bound, )*
+                {
+                    fn partial_cmp(&self, other: &Self) -> Option<::core::cmp::Ordering> {
+                        #partial_cmp_body
+                    }
+                }
+            });
+        }
+    }
+
+    // `manual_clone` replaces a derived `Clone`, whose bounds land on the
+    // generic parameters, with one bounded on the field types.
+    let clone_tokens = if partial_args.manual_clone {
+        derive_traits.retain(|path| !is_std_derive(path, "Clone"));
+        let clone_doc = "Clones each field; only requires the field types to implement `Clone`.";
+        let clone_types = included_fields
+            .iter()
+            .map(|field| included_ty(field))
+            .chain(
+                optional_fields
+                    .iter()
+                    .map(|field| repr_of(field).field_ty()),
+            );
+        let clone_idents = included_fields
+            .iter()
+            .chain(optional_fields.iter())
+            .map(|field| &field.ident);
+        quote! {
+            #[doc = #clone_doc]
+            impl #partial_impl_generics ::core::clone::Clone for #target_ident #partial_ty_generics
+            where
+                #( #partial_predicates, )*
+                #( #clone_types: ::core::clone::Clone, )*
+            {
+                #[inline]
+                fn clone(&self) -> Self {
+                    Self {
+                        #( #clone_idents: ::core::clone::Clone::clone(&self.#clone_idents), )*
+                    }
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+    let cfg_derive_attrs = partial_args.cfg_derives.iter().map(|(predicate, traits)| {
+        quote! { #[cfg_attr(#predicate, derive( #(#traits),* ))] }
+    });
+    let derives = if !derive_traits.is_empty() {
+        quote! {
+            #[derive( #(#derive_traits),* )]
+            #(#cfg_derive_attrs)*
+        }
+    } else {
+        quote! { #(#cfg_derive_attrs)* }
+    };
+
+    let method_name_str = match &partial_args.rebuild_fn {
+        Some(name) => name.unraw().to_string(),
+        None => format!("to_{}", orig_name.unraw().to_string().to_snake_case()),
+    };
+    let method_ident = Ident::new(&method_name_str, Span::call_site());
+    let cloned_method_name_str = format!("{}_cloned", method_name_str);
+    let cloned_method_ident = Ident::new(&cloned_method_name_str, Span::call_site());
+    let or_current_method_ident = Ident::new(
+        &format!("{}_or_current", method_name_str),
+        Span::call_site(),
+    );
+    let or_current_doc = "Applies this partial struct on top of `current`: included fields are always taken from this struct, optional fields only when `Some`, and omitted fields are kept from `current`.";
+    let from_base_method_ident =
+        Ident::new(&format!("{}_from_base", method_name_str), Span::call_site());
+    let must_use_attr = if partial_args.skip_must_use {
+        quote! {}
+    } else {
+        quote! { #[must_use] }
+    };
+    // With a default for every omitted field and no fallback parameters,
+    // the full struct can be rebuilt without any arguments. A `default(...)`
+    // entry takes precedence over the field's `#[partial_default(...)]`.
+    let omitted_default_exprs: Vec<_> = omitted_fields
+        .iter()
+        .filter_map(|field| {
+            let name = field.ident.as_ref()?.unraw().to_string();
+            partial_args
+                .omitted_defaults
+                .iter()
+                .find(|(ident, _)| ident.unraw() == name)
+                .map(|(_, expr)| expr)
+                .or_else(|| field_args.get(&name).and_then(|args| args.default.as_ref()))
+        })
+        .collect();
+    let defaulted_tokens = if !omitted_fields.is_empty()
+        && omitted_default_exprs.len() == omitted_fields.len()
+        && fallback_fields.is_empty()
+    {
+        let defaulted_method_ident =
+            Ident::new(&format!("{}_defaulted", method_name_str), Span::call_site());
+        let defaulted_doc = format!(
+            "Rebuilds the full struct, filling every omitted field from its default. Same as [`Self::{}`] with those values.",
+            method_ident
+        );
+        // `impl Into<T>` parameters leave literals such as `0` untyped.
+        let default_args =
+            omitted_fields
+                .iter()
+                .zip(&omitted_default_exprs)
+                .map(|(field, expr)| {
+                    let ty = &field.ty;
+                    if partial_args.into_params {
+                        quote! { ::core::convert::identity::<#ty>(#expr) }
+                    } else {
+                        quote! { #expr }
+                    }
+                });
+        quote! {
+            #[doc = #defaulted_doc]
+            #must_use_attr
+            #[inline]
+            pub fn #defaulted_method_ident #method_generics (self) -> #orig_name #orig_ty_generics
+            where
+                #( #orig_predicates, )*
+            {
+                self.#method_ident( #(#default_args),* )
+            }
+        }
+    } else {
+        quote! {}
+    };
+    let from_base_doc = format!(
+        "Rebuilds the full struct, taking the omitted fields (and any `None` optional fields) from `base`. Same as [`Self::{}`].",
+        or_current_method_ident
+    );
+    // The bounds are higher-ranked so that a field type without
+    // `PartialEq` only makes `diff` unusable instead of failing to compile.
+    let diff_doc = "Returns the names of the fields whose value differs from `full`, in declaration order. Optional fields are only compared when `Some`; omitted fields are ignored.";
+    let diff_types = included_fields
+        .iter()
+        .chain(optional_fields.iter())
+        .map(|field| &field.ty);
+    let diff_statements: Vec<_> = field_kinds
+        .iter()
+        .filter(|(.., kind)| *kind != FieldKind::Omitted)
+        .flat_map(|&(field, _, name, kind)| match flatten_of(name) {
+            Some(flatten) => flatten.fields.iter().map(|inner| (inner, kind)).collect(),
+            None => vec![(field, kind)],
+        })
+        .map(|(field, kind)| {
+            let ident = &field.ident;
+            let name = ident
+                .as_ref()
+                .map(|id| id.unraw().to_string())
+                .unwrap_or_default();
+            let source = source_of(field);
+            if kind == FieldKind::Optional {
+                let value = repr_of(field).borrow(quote! { self.#ident });
+                quote! {
+                    if let Some(value) = #value {
+                        if *value != full.#source {
+                            changed.push(#name);
+                        }
+                    }
+                }
+            } else {
+                let value = read_included(quote! { self.#ident });
+                quote! {
+                    if #value != full.#source {
+                        changed.push(#name);
+                    }
+                }
+            }
+        })
+        .collect();
+    let diff_body = if diff_statements.is_empty() {
+        quote! {
+            let _ = full;
+            ::std::vec::Vec::new()
+        }
+    } else {
+        quote! {
+            let mut changed = ::std::vec::Vec::new();
+            #( #diff_statements )*
+            changed
+        }
+    };
+    // Layering patches: later `Some` values win, `None` keeps what is there.
+    let overlay_tokens = if optional_fields.is_empty() {
+        quote! {}
+    } else {
+        let overlay_doc = "Layers `other` on top of this partial struct: `other`'s included fields and `Some` optional fields win, and `None` keeps this struct's value.";
+        let extend_doc = "Overlays each partial struct in order, so the last `Some` value of every optional field wins.";
+        let included_idents: Vec<_> = included_fields.iter().map(|field| &field.ident).collect();
+        let overlaid = optional_fields.iter().map(|field| {
+            let ident = &field.ident;
+            let repr = repr_of(field);
+            let other = repr.take(quote! { other.#ident });
+            let current = repr.take(quote! { self.#ident });
+            let value = repr.wrap_option(quote! { #other.or(#current) });
+            quote! { #ident: #value }
+        });
+        let patched = optional_fields.iter().map(|field| {
+            let ident = &field.ident;
+            let repr = repr_of(field);
+            let patch = repr.take(quote! { patch.#ident });
+            let value = repr.wrap(quote! { value });
+            quote! {
+                if let Some(value) = #patch {
+                    self.#ident = #value;
+                }
+            }
+        });
+        quote! {
+            impl #partial_impl_generics #target_ident #partial_ty_generics #partial_where_clause {
+                #[doc = #overlay_doc]
+                #[inline]
+                pub fn overlay(self, other: Self) -> Self {
+                    Self {
+                        #( #included_idents: other.#included_idents, )*
+                        #( #overlaid, )*
+                    }
+                }
+            }
+
+            #[doc = #extend_doc]
+            impl #partial_impl_generics ::core::iter::Extend<#target_ident #partial_ty_generics> for #target_ident #partial_ty_generics #partial_where_clause {
+                fn extend<__PartialIter: ::core::iter::IntoIterator<Item = Self>>(&mut self, patches: __PartialIter) {
+                    for patch in patches {
+                        #( self.#included_idents = patch.#included_idents; )*
+                        #( #patched )*
+                    }
+                }
+            }
+        }
+    };
+    let merge_into_doc = "Writes this partial struct into `target`: included fields are always assigned, optional fields only when `Some`. Omitted fields are left untouched.";
+    let merge_into_statements = included_fields
+        .iter()
+        .map(|field| {
+            let ident = &field.ident;
+            let source = source_of(field);
+            let value = unwrap_included(quote! { self.#ident });
+            quote! { target.#source = #value; }
+        })
+        .chain(optional_fields.iter().map(|field| {
+            let ident = &field.ident;
+            let source = source_of(field);
+            let value = repr_of(field).take(quote! { self.#ident });
+            quote! {
+                if let Some(value) = #value {
+                    target.#source = value;
+                }
+            }
+        }));
+
+    // Doc generation remains the same
+    let omitted_field_names_list: Vec<String> = omitted_fields
+        .iter()
+        .filter_map(|f| f.ident.as_ref().map(|id| id.unraw().to_string()))
+        .collect();
+    let omitted_fields_desc = if omitted_field_names_list.is_empty() {
+        "including all fields".to_string()
+    } else {
+        format!(
+            "omitting the field(s): {}",
+            omitted_field_names_list.join(", ")
+        )
+    };
+    let optional_fields_desc = if optional_fields.is_empty() {
+        String::new()
+    } else {
+        let optional_field_names_list: Vec<String> = optional_fields
+            .iter()
+            .filter_map(|f| f.ident.as_ref().map(|id| id.unraw().to_string()))
+            .collect();
+        format!(
+            "; fields made optional: {}",
+            optional_field_names_list.join(", ")
+        )
+    };
+    // `forward_attrs(...)` narrows which field attributes are copied.
+    let attrs_desc = match forward_attrs {
+        Some([]) => {
+            "Field attributes are not copied, apart from doc comments and `deprecated`.".to_string()
+        }
+        Some(names) => format!(
+            "Of the field attributes, only doc comments, `deprecated` and {} are copied.",
+            names
+                .iter()
+                .map(|name| format!("`{}`", name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        None => "Field attributes are copied.".to_string(),
+    };
+    let struct_doc = format!(
+        "A partial version of `{}` {}{}. {}",
+        orig_name, omitted_fields_desc, optional_fields_desc, attrs_desc
+    );
+    // `repr` is only copied on request: with fields omitted or made
+    // optional, the partial's layout no longer matches the original.
+    let repr_attrs: Vec<_> = if partial_args.keep_repr {
+        ast.attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("repr"))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let struct_attrs = ast.attrs.iter().filter(|attr| {
+        partial_args
+            .forward_struct_attrs
+            .iter()
+            .any(|name| attr.path().is_ident(name))
+    });
+    let repr_doc = if repr_attrs.is_empty() {
+        quote! {}
+    } else {
+        let repr_doc = format!(
+            "Keeps the `#[repr(...)]` of `{}`, but its fields differ, so the two layouts are not interchangeable.",
+            orig_name
+        );
+        quote! {
+            #[doc = ""]
+            #[doc = #repr_doc]
+        }
+    };
+    // A `repr(C)` partial is only layout-compatible with the original when
+    // it is a prefix of it, so each kept field must sit at the offset it
+    // has in the original. Generic structs are skipped, as `offset_of!`
+    // needs concrete types outside of a function.
+    let is_repr_c = repr_attrs.iter().any(|attr| {
+        let mut found = false;
+        let _ = attr.parse_nested_meta(|meta| {
+            found |= meta.path.is_ident("C");
+            Ok(())
+        });
+        found
+    });
+    let layout_assert_tokens = if is_repr_c && ast.generics.params.is_empty() {
+        let checks = field_kinds
+            .iter()
+            .filter(|&&(_, _, name, kind)| kind != FieldKind::Omitted && flatten_of(name).is_none())
+            .map(|&(field, ident, name, _)| {
+                let partial_ident = &field.ident;
+                let message = format!(
+                    "`{}` keeps `#[repr(C)]`, but its field `{}` does not sit at the same offset as in `{}`; only trailing fields can be omitted",
+                    target_ident, name, orig_name.unraw()
+                );
+                quote! {
+                    assert!(
+                        ::core::mem::offset_of!(#target_ident, #partial_ident) == ::core::mem::offset_of!(#orig_name, #ident),
+                        #message
+                    );
+                }
+            });
+        let size_message = format!(
+            "`{}` keeps `#[repr(C)]`, but is larger than `{}`",
+            target_ident,
+            orig_name.unraw()
+        );
+        quote! {
+            const _: () = {
+                #(#checks)*
+                assert!(::core::mem::size_of::<#target_ident>() <= ::core::mem::size_of::<#orig_name>(), #size_message);
+            };
+        }
+    } else {
+        quote! {}
+    };
+    let consuming_method_doc =
+        "Converts this partial struct into the full struct by providing the omitted fields.";
+    let cloned_method_doc1 = "Creates a new full struct by cloning the fields from this partial struct and providing the omitted fields.";
+    let cloned_method_doc2 = "Requires that all included fields implement `Clone`.";
+    let from_impl_doc =
+        "Converts the full struct into this partial struct by projecting the included fields.";
+    let from_with_omitted_doc =
+        "Splits the full struct into this partial struct and a struct containing the omitted fields.";
+    let into_with_omitted_doc =
+        "Splits this struct into its partial representation and a struct containing the omitted fields.";
+    let take_omitted_doc = "Converts this partial struct into the full struct, taking the omitted fields from the struct returned by the split methods. Optional fields fall back to the given values when `None`.";
+    let to_method_args: Vec<_> = omitted_fields
+        .iter()
+        .chain(fallback_fields.iter())
+        .map(|field| &field.ident)
+        .collect();
+    let with_method_ident = Ident::new(&format!("{}_with", method_name_str), Span::call_site());
+    let with_method_doc = "Converts this partial struct into the full struct, calling the given closure for the omitted fields as a tuple in declaration order. The closure only runs here, so costly values are computed only when the full struct is rebuilt.";
+    let omitted_types = omitted_fields.iter().map(|field| &field.ty);
+    let omitted_idents = omitted_fields.iter().map(|field| &field.ident);
+    let with_method_tokens = quote! {
+        #[doc = #with_method_doc]
+        #[inline]
+        pub fn #with_method_ident<#(#method_params,)* __PartialFn>(self, __partial_omitted: __PartialFn, #( #optional_fallback_params ),* ) -> #orig_name #orig_ty_generics
+        where
+            __PartialFn: FnOnce() -> ( #(#omitted_types,)* ),
+            #( #orig_predicates, )*
+        {
+            let ( #(#omitted_idents,)* ) = __partial_omitted();
+            self.#method_ident(#( #to_method_args ),*)
+        }
+    };
+    // Only partials that rebuild without arguments and without unwrapping
+    // can offer an infallible `complete()`. A partial made more visible
+    // with `vis = "..."` would leak a less visible original as `type Full`.
+    let complete_tokens = if omitted_fields.is_empty()
+        && fallback_fields.is_empty()
+        && (partial_args.vis.is_none() || matches!(ast.vis, syn::Visibility::Public(_)))
+    {
+        quote! {
+            impl #orig_impl_generics ::partial_struct::PartialComplete for #target_ident #partial_ty_generics #orig_where_clause {
+                type Full = #orig_name #orig_ty_generics;
+
+                #[inline]
+                fn complete(self) -> Self::Full {
+                    self.#method_ident()
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+    let fold_doc =
+        "Folds `f` over the names of the optional fields that are `Some`, in declaration order.";
+    let optional_name_strs: Vec<_> = optional_fields
+        .iter()
+        .filter_map(|field| field.ident.as_ref().map(|id| id.unraw().to_string()))
+        .collect();
+    let optional_present: Vec<_> = optional_fields
+        .iter()
+        .map(|field| {
+            let ident = &field.ident;
+            repr_of(field).borrow(quote! { self.#ident })
+        })
+        .collect();
+    let fold_body = if optional_fields.is_empty() {
+        quote! {
+            let _ = f;
+            init
+        }
+    } else {
+        quote! {
+            let mut f = f;
+            let mut acc = init;
+            #(
+                if #optional_present.is_some() {
+                    acc = f(acc, #optional_name_strs);
+                }
+            )*
+            acc
+        }
+    };
+    let present_optionals_doc =
+        "Returns the names of the optional fields that are `Some`, in declaration order.";
+    let absent_optionals_doc =
+        "Returns the names of the optional fields that are `None`, in declaration order.";
+    let absent_body = if optional_fields.is_empty() {
+        quote! { ::std::vec::Vec::new() }
+    } else {
+        quote! {
+            let mut absent = ::std::vec::Vec::new();
+            #(
+                if #optional_present.is_none() {
+                    absent.push(#optional_name_strs);
+                }
+            )*
+            absent
+        }
+    };
+    // Like `diff`, the bounds are higher-ranked so a field type without
+    // `Display` only makes `changed` unusable.
+    let changed_doc = "Returns the name and `Display`-formatted value of every optional field that is `Some`, in declaration order (for example to build an SQL `UPDATE ... SET` clause).";
+    let changed_types = optional_fields.iter().map(|field| &field.ty);
+    let changed_body = if optional_fields.is_empty() {
+        quote! { ::std::vec::Vec::new() }
+    } else {
+        quote! {
+            let mut changed = ::std::vec::Vec::new();
+            #(
+                if let Some(value) = #optional_present {
+                    changed.push((#optional_name_strs, ::std::string::ToString::to_string(value)));
+                }
+            )*
+            changed
+        }
+    };
+    let apply_if_doc = "Passes this partial struct through `f` when `cond` is true, and returns it unchanged otherwise.";
+    let source_struct_doc = "The name of the struct this partial struct was derived from.";
+    let source_struct_name = orig_name.unraw().to_string();
+    let included_names_doc =
+        "Returns the names of the fields kept in this partial struct, in declaration order.";
+    let omitted_names_doc =
+        "Returns the names of the fields omitted from this partial struct, in declaration order.";
+    let omitted_name_strs = &omitted_field_names_list;
+    let included_name_strs = field_kinds
+        .iter()
+        .filter(|(.., kind)| *kind != FieldKind::Omitted)
+        .flat_map(|&(_, _, name, _)| match flatten_of(name) {
+            Some(flatten) => flatten
+                .fields
+                .iter()
+                .filter_map(|field| field.ident.as_ref().map(|id| id.unraw().to_string()))
+                .collect(),
+            None => vec![partial_name(name)],
+        })
+        .collect::<Vec<_>>();
+    let (field_enum_tokens, field_enum_impl_tokens) = if partial_args.field_enum {
+        let field_enum_ident = Ident::new(&format!("{}Field", target_ident), Span::call_site());
+        let field_enum_doc = format!(
+            "The fields kept in `{}`, in declaration order.",
+            target_ident
+        );
+        let field_name_doc = "Returns the field name, as listed by `included_field_names()`.";
+        let variants: Vec<_> = included_name_strs
+            .iter()
+            .map(|name| Ident::new(&name.to_upper_camel_case(), Span::call_site()))
+            .collect();
+        let field_enum = quote! {
+            #[doc = #field_enum_doc]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            #dead_code_attr
+            #vis enum #field_enum_ident {
+                #(#variants,)*
+            }
+        };
+        let field_enum_impls = quote! {
+            impl #field_enum_ident {
+                #[doc = #field_name_doc]
+                #[inline]
+                pub const fn name(&self) -> &'static str {
+                    match *self {
+                        #(Self::#variants => #included_name_strs,)*
+                    }
+                }
+            }
+        };
+        (field_enum, field_enum_impls)
+    } else {
+        (quote! {}, quote! {})
+    };
+    let schema_doc = "Describes the fields of this partial struct, in declaration order. Optional fields report the type they wrap.";
+    let schema_entries = field_kinds
+        .iter()
+        .filter(|(.., kind)| *kind != FieldKind::Omitted)
+        .flat_map(|&(field, _, name, kind)| match flatten_of(name) {
+            Some(flatten) => flatten.fields.iter().map(|inner| (inner, kind)).collect(),
+            None => vec![(field, kind)],
+        })
+        .map(|(field, kind)| {
+            let name = field
+                .ident
+                .as_ref()
+                .map(|id| id.unraw().to_string())
+                .unwrap_or_default();
+            let ty = &field.ty;
+            let optional = kind == FieldKind::Optional;
+            quote! {
+                ::partial_struct::FieldSchema {
+                    name: #name,
+                    type_name: ::core::stringify!(#ty),
+                    optional: #optional,
+                }
+            }
+        });
+
+    let omitted_ident = Ident::new(&format!("{}Omitted", target_ident), Span::call_site());
+    let omitted_struct_doc = format!(
+        "Fields omitted from `{}` when projecting into `{}`.",
+        orig_name, target_ident
+    );
+
+    let omitted_fields_tokens = omitted_fields.iter().map(|field| {
+        let ident = &field.ident;
+        let ty = &field.ty;
+        let attrs = forwarded_attrs(field, forward_attrs);
+        quote! {
+            #(#attrs)*
+            pub #ident: #ty
+        }
+    });
+
+    let omitted_field_idents: Vec<_> = omitted_fields
+        .iter()
+        .filter_map(|field| field.ident.as_ref())
+        .collect();
+
+    let field_idents: Vec<_> = fields
+        .iter()
+        .filter_map(|field| field.ident.as_ref())
+        .collect();
+
+    let project_included = included_fields
+        .iter()
+        .map(|field| {
+            let ident = &field.ident;
+            let source = source_of(field);
+            let value = wrap_included(quote! { full.#source });
+            quote! { #ident: #value }
+        })
+        .chain(optional_fields.iter().map(|field| {
+            let ident = &field.ident;
+            let source = source_of(field);
+            let value = repr_of(field).wrap_full(quote! { full.#source });
+            quote! { #ident: #value }
+        }));
+    let from_impl_tokens = if partial_args.skip_from {
+        quote! {}
+    } else {
+        quote! {
+            #[doc = #from_impl_doc]
+            impl #orig_impl_generics From<#orig_name #orig_ty_generics> for #target_ident #partial_ty_generics #orig_where_clause {
+                #[inline]
+                fn from(full: #orig_name #orig_ty_generics) -> Self {
+                    Self {
+                        #(#project_included,)*
+                    }
+                }
+            }
+        }
+    };
+
+    let partial_from_full_assignments = included_fields
+        .iter()
+        .map(|field| {
+            let ident = &field.ident;
+            let source = source_of(field);
+            let value = wrap_included(quote! { #source });
+            quote! { #ident: #value }
+        })
+        .chain(optional_fields.iter().map(|field| {
+            let ident = &field.ident;
+            let source = source_of(field);
+            let value = repr_of(field).wrap_full(quote! { #source });
+            quote! { #ident: #value }
+        }));
+
+    let omitted_derive_traits = &partial_args.omitted_derive_traits;
+    let zeroize_derives = if partial_args.zeroize {
+        quote! { #[derive(::zeroize::Zeroize, ::zeroize::ZeroizeOnDrop)] }
+    } else {
+        quote! {}
+    };
+    let omitted_derives = if omitted_derive_traits.is_empty() {
+        zeroize_derives
+    } else {
+        quote! {
+            #[derive( #(#omitted_derive_traits),* )]
+            #zeroize_derives
+        }
+    };
+
+    let (omitted_struct_tokens, omitted_struct_ty, omitted_struct_ctor) =
+        if omitted_fields.is_empty() {
+            (quote! {}, quote! { () }, quote! { () })
+        } else if partial_args.omitted_as_tuple {
+            let omitted_types = omitted_fields.iter().map(|field| &field.ty);
+            (
+                quote! {},
+                quote! { ( #(#omitted_types,)* ) },
+                quote! { ( #(#omitted_field_idents,)* ) },
+            )
+        } else {
+            (
+                quote! {
+                    #[doc = #omitted_struct_doc]
+                    #omitted_derives
+                    #dead_code_attr
+                    #vis struct #omitted_ident #omitted_generics #omitted_where_clause {
+                        #(#omitted_fields_tokens,)*
+                    }
+                },
+                quote! { #omitted_ident #omitted_ty_generics },
+                quote! { #omitted_ident { #(#omitted_field_idents,)* } },
+            )
+        };
+
+    // Redacted fields are never formatted, so only the others need `Debug`.
+    let redact_tokens = if partial_args.redact_fields.is_empty() {
+        quote! {}
+    } else {
+        let redact_doc = format!(
+            "Formats the omitted fields, printing {} as `<redacted>`.",
+            partial_args
+                .redact_fields
+                .iter()
+                .map(|ident| format!("`{}`", ident.unraw()))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        let is_redacted = |field: &Field| {
+            field.ident.as_ref().is_some_and(|ident| {
+                partial_args
+                    .redact_fields
+                    .iter()
+                    .any(|redacted| redacted.unraw() == ident.unraw())
+            })
+        };
+        let omitted_predicates = omitted_generics
+            .where_clause
+            .iter()
+            .flat_map(|where_clause| where_clause.predicates.iter());
+        let shown_types = omitted_fields
+            .iter()
+            .filter(|field| !is_redacted(field))
+            .map(|field| &field.ty);
+        let debug_fields = omitted_fields.iter().map(|field| {
+            let ident = &field.ident;
+            let name = ident
+                .as_ref()
+                .map(|id| id.unraw().to_string())
+                .unwrap_or_default();
+            if is_redacted(field) {
+                quote! { .field(#name, &format_args!("<redacted>")) }
+            } else {
+                quote! { .field(#name, &self.#ident) }
+            }
+        });
+        let omitted_name = omitted_ident.to_string();
+        quote! {
+            #[doc = #redact_doc]
+            impl #omitted_impl_generics ::core::fmt::Debug for #omitted_ident #omitted_ty_generics
+            where
+                #( #omitted_predicates, )*
+                #( #shown_types: ::core::fmt::Debug, )*
+            {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    f.debug_struct(#omitted_name)
+                        #(#debug_fields)*
+                        .finish()
+                }
+            }
+        }
+    };
+
+    // `from_ref` and `from_shared` both project a borrowed full struct by
+    // cloning the included fields.
+    let mut borrowed_sources = Vec::new();
+    if partial_args.from_ref {
+        borrowed_sources.push((
+            quote! { &#orig_name #orig_ty_generics },
+            "Converts a borrowed full struct into this partial struct by cloning the included fields.",
+        ));
+    }
+    if partial_args.from_shared {
+        let shared_from_doc = "Converts a shared full struct into this partial struct by cloning the included fields.";
+        borrowed_sources.push((
+            quote! { &::std::sync::Arc<#orig_name #orig_ty_generics> },
+            shared_from_doc,
+        ));
+        borrowed_sources.push((
+            quote! { &::std::rc::Rc<#orig_name #orig_ty_generics> },
+            shared_from_doc,
+        ));
+    }
+    let cloned_from_tokens = if !borrowed_sources.is_empty() {
+        let cloned_field_types: Vec<_> = included_fields
+            .iter()
+            .chain(optional_fields.iter())
+            .map(|field| &field.ty)
+            .collect();
+        let project_cloned: Vec<_> = included_fields
+            .iter()
+            .map(|field| {
+                let ident = &field.ident;
+                let source = source_of(field);
+                let value = wrap_included(quote! { full.#source.clone() });
+                quote! { #ident: #value }
+            })
+            .chain(optional_fields.iter().map(|field| {
+                let ident = &field.ident;
+                let source = source_of(field);
+                let value = repr_of(field).wrap_full(quote! { full.#source.clone() });
+                quote! { #ident: #value }
+            }))
+            .collect();
+        let impls = borrowed_sources.iter().map(|(source, doc)| {
+            quote! {
+                #[doc = #doc]
+                impl #orig_impl_generics From<#source> for #target_ident #partial_ty_generics
+                where
+                    #( #orig_predicates, )*
+                    #( #cloned_field_types: Clone, )*
+                {
+                    #[inline]
+                    fn from(full: #source) -> Self {
+                        Self {
+                            #(#project_cloned,)*
+                        }
+                    }
+                }
+            }
+        });
+        quote! { #(#impls)* }
+    } else {
+        quote! {}
+    };
+
+    let (borrowed_struct_tokens, borrowed_impl_tokens) = if partial_args.borrowed {
+        let ref_ident = Ident::new(&format!("{}Ref", target_ident), Span::call_site());
+        let ref_lifetime = syn::Lifetime::new("'__partial", orig_name.span());
+        let mut ref_generics = partial_generics.clone();
+        ref_generics
+            .params
+            .insert(0, syn::parse_quote!(#ref_lifetime));
+        let (ref_impl_generics, ref_ty_generics, _) = ref_generics.split_for_impl();
+        let mut full_ref_generics = ast.generics.clone();
+        full_ref_generics
+            .params
+            .insert(0, syn::parse_quote!(#ref_lifetime));
+        let (full_ref_impl_generics, _, _) = full_ref_generics.split_for_impl();
+        let ref_struct_doc = format!("A borrowed view of `{}`.", target_ident);
+        let as_ref_doc = "Borrows each field of this partial struct.";
+        let ref_from_full_doc = "Borrows the included fields of the full struct.";
+        let into_owned_doc = "Clones each borrowed field into an owned partial struct.";
+
+        let ref_fields_tokens = included_fields
+            .iter()
+            .map(|field| {
+                let ident = &field.ident;
+                let ty = &field.ty;
+                quote! { pub #ident: &#ref_lifetime #ty }
+            })
+            .chain(optional_fields.iter().map(|field| {
+                let ident = &field.ident;
+                let ty = &field.ty;
+                quote! { pub #ident: Option<&#ref_lifetime #ty> }
+            }));
+        let borrow_partial = included_fields
+            .iter()
+            .map(|field| {
+                let ident = &field.ident;
+                quote! { #ident: &self.#ident }
+            })
+            .chain(optional_fields.iter().map(|field| {
+                let ident = &field.ident;
+                let value = repr_of(field).borrow(quote! { self.#ident });
+                quote! { #ident: #value }
+            }));
+        let borrow_full = included_fields
+            .iter()
+            .map(|field| {
+                let ident = &field.ident;
+                let source = source_of(field);
+                quote! { #ident: &full.#source }
+            })
+            .chain(optional_fields.iter().map(|field| {
+                let ident = &field.ident;
+                let source = source_of(field);
+                let value = repr_of(field).borrow_full(quote! { full.#source });
+                quote! { #ident: #value }
+            }));
+        let clone_owned = included_fields
+            .iter()
+            .map(|field| {
+                let ident = &field.ident;
+                quote! { #ident: self.#ident.clone() }
+            })
+            .chain(optional_fields.iter().map(|field| {
+                let ident = &field.ident;
+                let value = repr_of(field).wrap_option(quote! { self.#ident.cloned() });
+                quote! { #ident: #value }
+            }));
+        let owned_field_types = included_fields
+            .iter()
+            .chain(optional_fields.iter())
+            .map(|field| &field.ty);
+
+        let ref_struct = quote! {
+            #[doc = #ref_struct_doc]
+            #dead_code_attr
+            #vis struct #ref_ident #ref_generics #partial_where_clause {
+                #(#ref_fields_tokens,)*
+            }
+        };
+        let ref_impls = quote! {
+            impl #partial_impl_generics #target_ident #partial_ty_generics #partial_where_clause {
+                #[doc = #as_ref_doc]
+                #[inline]
+                pub fn as_partial_ref<#ref_lifetime>(&#ref_lifetime self) -> #ref_ident #ref_ty_generics {
+                    #ref_ident {
+                        #(#borrow_partial,)*
+                    }
+                }
+            }
+
+            #[doc = #ref_from_full_doc]
+            impl #full_ref_impl_generics From<&#ref_lifetime #orig_name #orig_ty_generics> for #ref_ident #ref_ty_generics #orig_where_clause {
+                #[inline]
+                fn from(full: &#ref_lifetime #orig_name #orig_ty_generics) -> Self {
+                    Self {
+                        #(#borrow_full,)*
+                    }
+                }
+            }
+
+            impl #ref_impl_generics #ref_ident #ref_ty_generics #partial_where_clause {
+                #[doc = #into_owned_doc]
+                #[inline]
+                pub fn into_owned(self) -> #target_ident #partial_ty_generics
+                where
+                    #( #owned_field_types: Clone, )*
+                {
+                    #target_ident {
+                        #(#clone_owned,)*
+                    }
+                }
+            }
+        };
+        (ref_struct, ref_impls)
+    } else {
+        (quote! {}, quote! {})
+    };
+
+    // The view mirrors the full struct, borrowing kept fields from the
+    // partial and omitted ones from the caller. Flattened fields are
+    // borrowed one by one, as the partial holds no embedded struct.
+    let (view_struct_tokens, view_impl_tokens) = if partial_args.view {
+        let view_ident = Ident::new(&format!("{}View", target_ident), Span::call_site());
+        let view_lifetime = syn::Lifetime::new("'__partial", orig_name.span());
+        let view_fields: Vec<(&Field, &Ident, FieldKind)> = field_kinds
+            .iter()
+            .flat_map(|&(field, ident, name, kind)| {
+                match flatten_of(name).filter(|_| kind != FieldKind::Omitted) {
+                    Some(flatten) => flatten
+                        .fields
+                        .iter()
+                        .filter_map(|inner| Some((inner, inner.ident.as_ref()?, kind)))
+                        .collect(),
+                    None => vec![(field, ident, kind)],
+                }
+            })
+            .collect();
+        let mut view_generics = generics_for_fields(
+            &ast.generics,
+            &view_fields
+                .iter()
+                .map(|&(field, ..)| field)
+                .collect::<Vec<_>>(),
+        );
+        view_generics
+            .params
+            .insert(0, syn::parse_quote!(#view_lifetime));
+        let (_, view_ty_generics, view_where_clause) = view_generics.split_for_impl();
+        let view_method_ident = Ident::new(&format!("{}_ref", method_name_str), Span::call_site());
+        let view_struct_doc = format!(
+            "A borrowed view of `{}`, built from a `{}` and the omitted fields.",
+            orig_name.unraw(),
+            target_ident
+        );
+        let view_method_doc = format!(
+            "Borrows this partial struct and the omitted fields as a `{}` without moving or cloning anything. Optional fields stay `None` when unset.",
+            view_ident
+        );
+        let view_fields_tokens = view_fields.iter().map(|&(field, ident, kind)| {
+            let ty = &field.ty;
+            if kind == FieldKind::Optional {
+                quote! { pub #ident: Option<&#view_lifetime #ty> }
+            } else {
+                quote! { pub #ident: &#view_lifetime #ty }
+            }
+        });
+        let view_params = omitted_fields.iter().map(|field| {
+            let ident = &field.ident;
+            let ty = &field.ty;
+            quote! { #ident: &#view_lifetime #ty }
+        });
+        let view_assignments = view_fields.iter().map(|&(field, ident, kind)| {
+            let partial_ident = &field.ident;
+            match kind {
+                FieldKind::Omitted => quote! { #ident },
+                FieldKind::Optional => {
+                    let value = repr_of(field).borrow(quote! { self.#partial_ident });
+                    quote! { #ident: #value }
+                }
+                FieldKind::Included => {
+                    let value = borrow_included(quote! { self.#partial_ident });
+                    quote! { #ident: #value }
+                }
+            }
+        });
+        let view_struct = quote! {
+            #[doc = #view_struct_doc]
+            #dead_code_attr
+            #vis struct #view_ident #view_generics #view_where_clause {
+                #(#view_fields_tokens,)*
+            }
+        };
+        let view_impls = quote! {
+            impl #partial_impl_generics #target_ident #partial_ty_generics #partial_where_clause {
+                #[doc = #view_method_doc]
+                #[inline]
+                pub fn #view_method_ident<#view_lifetime, #(#method_params),*>(&#view_lifetime self, #(#view_params),*) -> #view_ident #view_ty_generics
+                where
+                    #( #orig_predicates, )*
+                {
+                    #view_ident {
+                        #(#view_assignments,)*
+                    }
+                }
+            }
+        };
+        (view_struct, view_impls)
+    } else {
+        (quote! {}, quote! {})
+    };
+
+    let maps_to_doc =
+        "Converts this partial struct into a structurally identical type, field by field.";
+    let partial_field_idents: Vec<_> = included_fields
+        .iter()
+        .chain(optional_fields.iter())
+        .filter_map(|field| field.ident.as_ref())
+        .collect();
+    // The exhaustive pattern makes the compiler reject a source struct
+    // with extra fields, and the struct literal one with missing fields.
+    let structural_from_doc =
+        "Converts a structurally identical type into this partial struct, field by field.";
+    let structural_from_tokens = partial_args.structural_from.iter().map(|other| {
+        let pattern = expr_path(other);
+        let destructure = quote_spanned! {other.span()=>
+            let #pattern { #(#partial_field_idents,)* } = other;
+        };
+        quote! {
+            #[doc = #structural_from_doc]
+            impl #partial_impl_generics From<#other> for #target_ident #partial_ty_generics #partial_where_clause {
+                #[inline]
+                fn from(other: #other) -> Self {
+                    #destructure
+                    Self {
+                        #(#partial_field_idents,)*
+                    }
+                }
+            }
+        }
+    });
+    // A partial made only of optional fields has a natural empty value.
+    let empty_tokens = if included_fields.is_empty() && !optional_fields.is_empty() {
+        let empty_doc = "Returns a partial struct with every field unset, to build a patch from.";
+        let reprs: Vec<_> = optional_fields.iter().map(|field| repr_of(field)).collect();
+        // Wrappers are built through a trait call, which cannot be `const`.
+        let constness = if reprs.iter().any(|repr| repr.via.is_some()) {
+            quote! {}
+        } else {
+            quote! { const }
+        };
+        let unset = optional_fields.iter().zip(&reprs).map(|(field, repr)| {
+            let ident = &field.ident;
+            let value = repr.wrap_option(quote! { None });
+            quote! { #ident: #value }
+        });
+        quote! {
+            impl #partial_impl_generics #target_ident #partial_ty_generics #partial_where_clause {
+                #[doc = #empty_doc]
+                #[inline]
+                pub #constness fn empty() -> Self {
+                    Self {
+                        #(#unset,)*
+                    }
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+    let accessor_tokens = if partial_args.accessors {
+        let accessors = optional_fields.iter().map(|field| {
+            let ident = &field.ident;
+            let ty = &field.ty;
+            let name = ident
+                .as_ref()
+                .map(|id| id.unraw().to_string())
+                .unwrap_or_default();
+            let mut_ident = Ident::new(&format!("{}_mut", name), Span::call_site());
+            let get_doc = format!("Borrows `{}` if it is set.", name);
+            let mut_doc = format!("Mutably borrows `{}` if it is set.", name);
+            let repr = repr_of(field);
+            let get = repr.borrow(quote! { self.#ident });
+            let get_mut = repr.borrow_mut(quote! { self.#ident });
+            quote! {
+                #[doc = #get_doc]
+                #[inline]
+                pub fn #ident(&self) -> Option<&#ty> {
+                    #get
+                }
+
+                #[doc = #mut_doc]
+                #[inline]
+                pub fn #mut_ident(&mut self) -> Option<&mut #ty> {
+                    #get_mut
+                }
+            }
+        });
+        quote! {
+            impl #partial_impl_generics #target_ident #partial_ty_generics #partial_where_clause {
+                #(#accessors)*
+            }
+        }
+    } else {
+        quote! {}
+    };
+    let maps_to_tokens = partial_args.maps_to.iter().map(|other| {
+        quote! {
+            #[doc = #maps_to_doc]
+            impl #partial_impl_generics From<#target_ident #partial_ty_generics> for #other #partial_where_clause {
+                #[inline]
+                fn from(partial: #target_ident #partial_ty_generics) -> Self {
+                    Self {
+                        #(#partial_field_idents: partial.#partial_field_idents,)*
+                    }
+                }
+            }
+        }
+    });
+
+    let cross_eq_tokens = if partial_args.cross_eq {
+        let cross_eq_doc = "Compares the included fields with the full struct, ignoring omitted fields. Optional fields only match when they hold the full struct's value.";
+        let compared_types = included_fields
+            .iter()
+            .chain(optional_fields.iter())
+            .map(|field| &field.ty);
+        let comparisons: Vec<_> = included_fields
+            .iter()
+            .map(|field| {
+                let ident = &field.ident;
+                let source = source_of(field);
+                let value = read_included(quote! { partial.#ident });
+                quote! { #value == full.#source }
+            })
+            .chain(optional_fields.iter().map(|field| {
+                let ident = &field.ident;
+                let repr = repr_of(field);
+                let value = repr.borrow(quote! { partial.#ident });
+                let source = source_of(field);
+                let full_value = repr.borrow_full(quote! { full.#source });
+                quote! { #value == #full_value }
+            }))
+            .collect();
+        let comparison = if comparisons.is_empty() {
+            quote! { true }
+        } else {
+            quote! { #(#comparisons)&&* }
+        };
+        quote! {
+            #[doc = #cross_eq_doc]
+            impl #orig_impl_generics PartialEq<#orig_name #orig_ty_generics> for #target_ident #partial_ty_generics
+            where
+                #( #orig_predicates, )*
+                #( #compared_types: PartialEq, )*
+            {
+                #[inline]
+                fn eq(&self, full: &#orig_name #orig_ty_generics) -> bool {
+                    let partial = self;
+                    #comparison
+                }
+            }
+
+            #[doc = #cross_eq_doc]
+            impl #orig_impl_generics PartialEq<#target_ident #partial_ty_generics> for #orig_name #orig_ty_generics
+            where
+                #target_ident #partial_ty_generics: PartialEq<#orig_name #orig_ty_generics>,
+                #( #orig_predicates, )*
+            {
+                #[inline]
+                fn eq(&self, partial: &#target_ident #partial_ty_generics) -> bool {
+                    partial == self
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let log_missing_tokens = if partial_args.log_missing {
+        let logged_method_ident = Ident::new(
+            &format!("{}_logged_missing", method_name_str),
+            Span::call_site(),
+        );
+        let logged_method_doc = "Rebuilds the full struct from this partial struct and the omitted fields. If an optional field is `None`, logs a warning and returns the name of the first missing field.";
+        let omitted_params = omitted_fields.iter().map(|field| {
+            let ident = &field.ident;
+            let ty = &field.ty;
+            quote! { #ident: #ty }
+        });
+        let unwrap_optionals = optional_fields.iter().map(|field| {
+            let ident = &field.ident;
+            let value = repr_of(field).take(quote! { self.#ident });
+            if let Some(default) = default_of(field) {
+                return quote! {
+                    let #ident = #value.unwrap_or_else(|| #default);
+                };
+            }
+            let name = ident.as_ref().map(|id| id.unraw().to_string()).unwrap_or_default();
+            let orig_name_str = orig_name.unraw().to_string();
+            quote! {
+                let #ident = match #value {
+                    Some(value) => value,
+                    None => {
+                        ::log::warn!("cannot rebuild `{}`: optional field `{}` is missing", #orig_name_str, #name);
+                        return ::core::result::Result::Err(#name);
+                    }
+                };
+            }
+        });
+        let assignments = field_kinds.iter().map(|&(field, ident, name, kind)| {
+            let partial_ident = &field.ident;
+            match (kind, flatten_of(name)) {
+                (FieldKind::Included, Some(flatten)) => {
+                    let value = flatten.rebuild(|inner| unwrap_included(quote! { self.#inner }));
+                    quote! { #ident: #value }
+                }
+                (FieldKind::Included, None) => {
+                    let value = unwrap_included(quote! { self.#partial_ident });
+                    quote! { #ident: #value }
+                }
+                (FieldKind::Optional, _) => quote! { #ident: #partial_ident },
+                (FieldKind::Omitted, _) => quote! { #ident },
+            }
+        });
+        quote! {
+            impl #partial_impl_generics #target_ident #partial_ty_generics #partial_where_clause {
+                #[doc = #logged_method_doc]
+                pub fn #logged_method_ident #method_generics (self, #(#omitted_params),* ) -> ::core::result::Result<#orig_name #orig_ty_generics, &'static str>
+                where
+                    #( #orig_predicates, )*
+                {
+                    #(#unwrap_optionals)*
+                    ::core::result::Result::Ok(#orig_name {
+                        #(#assignments,)*
+                    })
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let from_with_omitted_method_name = format!(
+        "from_{}_with_omitted",
+        orig_name.unraw().to_string().to_snake_case()
+    );
+    let from_with_omitted_ident = Ident::new(&from_with_omitted_method_name, Span::call_site());
+    let usage_doc = format!(
+        "Split a `{}` with [`{}::{}`] and rebuild it with [`{}::{}`].",
+        orig_name, target_ident, from_with_omitted_method_name, target_ident, method_name_str
+    );
+
+    let into_with_omitted_method_name = format!(
+        "into_{}_with_omitted",
+        target_ident.to_string().to_snake_case()
+    );
+    let into_with_omitted_ident = Ident::new(&into_with_omitted_method_name, Span::call_site());
+    let apply_partial_ident = Ident::new(
+        &format!("apply_{}", target_ident.to_string().to_snake_case()),
+        Span::call_site(),
+    );
+    let apply_partial_doc = format!(
+        "Copies the fields of a `{}` onto this struct in place: included fields always, optional fields only when `Some`. Omitted fields are left untouched.",
+        target_ident
+    );
+
+    // Hashes the same values, in the same order, as a derived `Hash` on
+    // the partial: included fields as-is, optional fields as `Some(..)`.
+    let hash_helper_tokens = if partial_args.hash_helper {
+        let hash_doc = format!(
+            "Hashes only the fields kept in `{}`, matching the hash of the corresponding partial struct.",
+            target_ident
+        );
+        let hashed_types = included_fields
+            .iter()
+            .chain(optional_fields.iter())
+            .map(|field| &field.ty);
+        let hash_statements = included_fields
+            .iter()
+            .map(|field| {
+                let source = source_of(field);
+                quote! { ::core::hash::Hash::hash(&self.#source, &mut hasher); }
+            })
+            .chain(optional_fields.iter().map(|field| {
+                let source = source_of(field);
+                let value = repr_of(field).borrow_full(quote! { self.#source });
+                quote! { ::core::hash::Hash::hash(&#value, &mut hasher); }
+            }));
+        quote! {
+            impl #orig_impl_generics #orig_name #orig_ty_generics
+            where
+                #( #orig_predicates, )*
+                #( #hashed_types: ::core::hash::Hash, )*
+            {
+                #[doc = #hash_doc]
+                pub fn partial_hash(&self) -> u64 {
+                    let mut hasher = ::std::collections::hash_map::DefaultHasher::new();
+                    #( #hash_statements )*
+                    ::core::hash::Hasher::finish(&hasher)
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    // A `ZeroizeOnDrop` omitted struct implements `Drop`, so its fields
+    // can't be moved out; callers pass them to `to_<orig>` explicitly.
+    let take_omitted_tokens = if partial_args.zeroize && !omitted_fields.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            #[doc = #take_omitted_doc]
+            #[inline]
+            pub fn take_omitted #method_generics (self, __partial_omitted: #omitted_struct_ty, #( #optional_fallback_params ),* ) -> #orig_name #orig_ty_generics
+            where
+                #( #orig_predicates, )*
+            {
+                let #omitted_struct_ctor = __partial_omitted;
+                self.#method_ident(#( #to_method_args ),*)
+            }
+        }
+    };
+    // Omitted fields without a `default(...)` entry or `#[partial_default]`
+    // use `Default`; the bounds are higher-ranked so a type without it only
+    // makes the method unusable.
+    let omitted_defaults_tokens = if omitted_fields.is_empty() {
+        quote! {}
+    } else {
+        let mut defaulted_types = Vec::new();
+        let bindings = omitted_fields
+            .iter()
+            .map(|field| {
+                let ident = &field.ident;
+                let name = ident
+                    .as_ref()
+                    .map(|id| id.unraw().to_string())
+                    .unwrap_or_default();
+                let explicit = partial_args
+                    .omitted_defaults
+                    .iter()
+                    .find(|(id, _)| id.unraw() == name)
+                    .map(|(_, expr)| expr)
+                    .or_else(|| field_args.get(&name).and_then(|args| args.default.as_ref()));
+                match explicit {
+                    Some(expr) => quote! { let #ident = #expr; },
+                    None => {
+                        defaulted_types.push(&field.ty);
+                        quote! { let #ident = ::core::default::Default::default(); }
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+        let omitted_defaults_doc = "Returns the omitted fields filled from their `default(...)` entries, or `Default` for the others, ready for `take_omitted`.";
+        quote! {
+            #[doc = #omitted_defaults_doc]
+            #[inline]
+            pub fn omitted_defaults #method_generics () -> #omitted_struct_ty
+            where
+                #( #orig_predicates, )*
+                #( for<'__partial> #defaulted_types: ::core::default::Default, )*
+            {
+                #( #bindings )*
+                #omitted_struct_ctor
+            }
+        }
+    };
+    // The same conversion started from the omitted half, for when that half
+    // (IDs, timestamps) is produced first.
+    let omitted_into_tokens = if omitted_fields.is_empty()
+        || partial_args.omitted_as_tuple
+        || partial_args.zeroize
+    {
+        quote! {}
+    } else {
+        let omitted_method_params = extra_method_params(&ast.generics, &omitted_generics);
+        let omitted_method_generics = if omitted_method_params.is_empty() {
+            quote! {}
+        } else {
+            quote! { <#(#omitted_method_params),*> }
+        };
+        let into_full_ident = Ident::new(
+            &format!("into_{}", orig_name.unraw().to_string().to_snake_case()),
+            Span::call_site(),
+        );
+        let into_full_doc = format!(
+            "Rebuilds the full struct from these omitted fields and the given partial. Same as [`{}::take_omitted`].",
+            target_ident
+        );
+        let fallback_args = fallback_fields.iter().map(|field| &field.ident);
+        quote! {
+            impl #omitted_impl_generics #omitted_ident #omitted_ty_generics #omitted_where_clause {
+                #[doc = #into_full_doc]
+                #must_use_attr
+                #[inline]
+                pub fn #into_full_ident #omitted_method_generics (self, __partial_partial: #target_ident #partial_ty_generics, #( #optional_fallback_params ),* ) -> #orig_name #orig_ty_generics
+                where
+                    #( #orig_predicates, )*
+                {
+                    __partial_partial.take_omitted(self, #( #fallback_args ),*)
+                }
+            }
+        }
+    };
+    // Same constraint as `take_omitted`: the omitted struct must be destructurable.
+    let (try_rebuild_enum_tokens, try_rebuild_tokens) = if partial_args.try_rebuild
+        && (!partial_args.zeroize || omitted_fields.is_empty())
+    {
+        let missing_ident = Ident::new(&format!("{}MissingField", target_ident), Span::call_site());
+        let missing_doc = format!(
+            "The optional field of `{}` that was `None` when rebuilding `{}`.",
+            target_ident, orig_name
+        );
+        let try_rebuild_ident = Ident::new(&format!("try_{}", method_name_str), Span::call_site());
+        let try_rebuild_doc = format!(
+            "Rebuilds the full struct from this partial struct and the omitted fields, failing with the first optional field that is `None` instead of panicking like [`Self::{}`].",
+            method_ident
+        );
+        let fallback_names: Vec<String> = fallback_fields
+            .iter()
+            .filter_map(|field| field.ident.as_ref().map(|id| id.unraw().to_string()))
+            .collect();
+        let variants: Vec<_> = fallback_names
+            .iter()
+            .map(|name| Ident::new(&name.to_upper_camel_case(), Span::call_site()))
+            .collect();
+        let variant_docs = fallback_names
+            .iter()
+            .map(|name| format!("`{}` was `None`.", name));
+        let fallback_present = fallback_fields.iter().map(|field| {
+            let ident = &field.ident;
+            repr_of(field).borrow(quote! { self.#ident })
+        });
+        let omitted_args = omitted_fields.iter().map(|field| &field.ident);
+        let fallback_nones = fallback_fields.iter().map(|_| quote! { None });
+        let missing_enum = quote! {
+            #[doc = #missing_doc]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            #dead_code_attr
+            #vis enum #missing_ident {
+                #(
+                    #[doc = #variant_docs]
+                    #variants,
+                )*
+            }
+        };
+        let impls = quote! {
+            impl #missing_ident {
+                /// The name of the missing field.
+                pub fn field_name(&self) -> &'static str {
+                    match *self {
+                        #( Self::#variants => #fallback_names, )*
+                    }
+                }
+            }
+
+            impl ::core::fmt::Display for #missing_ident {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    write!(f, "missing optional field `{}`", self.field_name())
+                }
+            }
+
+            impl ::std::error::Error for #missing_ident {}
+
+            impl #partial_impl_generics #target_ident #partial_ty_generics #partial_where_clause {
+                #[doc = #try_rebuild_doc]
+                #[inline]
+                pub fn #try_rebuild_ident #method_generics (self, omitted: #omitted_struct_ty) -> ::core::result::Result<#orig_name #orig_ty_generics, #missing_ident>
+                where
+                    #( #orig_predicates, )*
+                {
+                    #(
+                        if #fallback_present.is_none() {
+                            return ::core::result::Result::Err(#missing_ident::#variants);
+                        }
+                    )*
+                    let #omitted_struct_ctor = omitted;
+                    ::core::result::Result::Ok(self.#method_ident(#( #omitted_args, )* #( #fallback_nones ),*))
+                }
+            }
+        };
+        (missing_enum, impls)
+    } else {
+        (quote! {}, quote! {})
+    };
+    let (freeze_struct_tokens, freeze_impl_tokens) = if partial_args.freeze_optional {
+        let missing_ident =
+            Ident::new(&format!("{}MissingFields", target_ident), Span::call_site());
+        let missing_doc = format!(
+            "The optional fields of `{}` that were `None` when completing `{}`, in declaration order.",
+            target_ident, orig_name
+        );
+        let complete_doc = format!(
+            "Rebuilds the full struct from this partial struct and the omitted fields, failing with every optional field that is `None` instead of panicking like [`Self::{}`].",
+            method_ident
+        );
+        let fallback_names = fallback_fields.iter().map(|field| {
+            field
+                .ident
+                .as_ref()
+                .map(|id| id.unraw().to_string())
+                .unwrap_or_default()
+        });
+        let fallback_present = fallback_fields.iter().map(|field| {
+            let ident = &field.ident;
+            repr_of(field).borrow(quote! { self.#ident })
+        });
+        let omitted_params = omitted_fields.iter().map(|field| {
+            let ident = &field.ident;
+            let ty = &field.ty;
+            quote! { #ident: #ty }
+        });
+        let omitted_args = omitted_fields.iter().map(|field| &field.ident);
+        let fallback_nones = fallback_fields.iter().map(|_| quote! { None });
+        let missing_struct = quote! {
+            #[doc = #missing_doc]
+            #[derive(Debug, Clone, PartialEq, Eq)]
+            #dead_code_attr
+            #vis struct #missing_ident {
+                /// The names of the missing fields.
+                pub fields: ::std::vec::Vec<&'static str>,
+            }
+        };
+        let impls = quote! {
+            impl ::core::fmt::Display for #missing_ident {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    f.write_str("missing optional fields: ")?;
+                    for (index, name) in self.fields.iter().enumerate() {
+                        if index > 0 {
+                            f.write_str(", ")?;
+                        }
+                        write!(f, "`{}`", name)?;
+                    }
+                    Ok(())
+                }
+            }
+
+            impl ::std::error::Error for #missing_ident {}
+
+            impl #partial_impl_generics #target_ident #partial_ty_generics #partial_where_clause {
+                #[doc = #complete_doc]
+                #[inline]
+                pub fn complete #method_generics (self, #( #omitted_params ),* ) -> ::core::result::Result<#orig_name #orig_ty_generics, #missing_ident>
+                where
+                    #( #orig_predicates, )*
+                {
+                    let mut __partial_fields = ::std::vec::Vec::new();
+                    #(
+                        if #fallback_present.is_none() {
+                            __partial_fields.push(#fallback_names);
+                        }
+                    )*
+                    if !__partial_fields.is_empty() {
+                        return ::core::result::Result::Err(#missing_ident { fields: __partial_fields });
+                    }
+                    ::core::result::Result::Ok(self.#method_ident(#( #omitted_args, )* #( #fallback_nones ),*))
+                }
+            }
+        };
+        (missing_struct, impls)
+    } else {
+        (quote! {}, quote! {})
+    };
+    let (try_complete_struct_tokens, try_complete_impl_tokens) = if partial_args.try_complete {
+        let error_ident = Ident::new(&format!("{}CompleteError", target_ident), Span::call_site());
+        let error_doc = format!(
+            "Why a `{}` could not be converted into `{}`.",
+            target_ident, orig_name
+        );
+        let try_from_doc = "Rebuilds the full struct: optional fields must be `Some` (or have a default), omitted fields are filled with `Default::default()`, and the result must pass `partial_struct::Validate`.";
+        let orig_name_str = orig_name.unraw().to_string();
+        let default_types = omitted_fields.iter().map(|field| &field.ty);
+        let assignments =
+            field_kinds.iter().map(
+                |&(field, ident, name, kind)| match (kind, default_of(field)) {
+                    (FieldKind::Omitted, _) => {
+                        quote! { #ident: ::core::default::Default::default() }
+                    }
+                    (FieldKind::Optional, Some(default)) => {
+                        let partial_ident = &field.ident;
+                        let value = repr_of(field).take(quote! { partial.#partial_ident });
+                        quote! { #ident: #value.unwrap_or_else(|| #default) }
+                    }
+                    (FieldKind::Optional, None) => {
+                        let partial_ident = &field.ident;
+                        let value = repr_of(field).take(quote! { partial.#partial_ident });
+                        quote! { #ident: #value.ok_or(#error_ident::MissingField(#name))? }
+                    }
+                    (FieldKind::Included, _) => match flatten_of(name) {
+                        Some(flatten) => {
+                            let value =
+                                flatten.rebuild(|inner| unwrap_included(quote! { partial.#inner }));
+                            quote! { #ident: #value }
+                        }
+                        None => {
+                            let partial_ident = &field.ident;
+                            let value = unwrap_included(quote! { partial.#partial_ident });
+                            quote! { #ident: #value }
+                        }
+                    },
+                },
+            );
+        let error_enum = quote! {
+            #[doc = #error_doc]
+            #[derive(Debug, Clone, PartialEq, Eq)]
+            #dead_code_attr
+            #vis enum #error_ident {
+                /// An optional field without a default was `None`.
+                MissingField(&'static str),
+                /// The rebuilt struct was rejected by `partial_struct::Validate`.
+                Invalid(::std::string::String),
+            }
+        };
+        let impls = quote! {
+            impl ::core::fmt::Display for #error_ident {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    match self {
+                        Self::MissingField(name) => write!(f, "missing field `{}` for `{}`", name, #orig_name_str),
+                        Self::Invalid(message) => write!(f, "invalid `{}`: {}", #orig_name_str, message),
+                    }
+                }
+            }
+
+            impl ::std::error::Error for #error_ident {}
+
+            #[doc = #try_from_doc]
+            impl #orig_impl_generics ::core::convert::TryFrom<#target_ident #partial_ty_generics> for #orig_name #orig_ty_generics
+            where
+                #( #orig_predicates, )*
+                #( #default_types: ::core::default::Default, )*
+                #orig_name #orig_ty_generics: ::partial_struct::Validate,
+            {
+                type Error = #error_ident;
+
+                fn try_from(partial: #target_ident #partial_ty_generics) -> ::core::result::Result<Self, Self::Error> {
+                    let full = #orig_name {
+                        #( #assignments, )*
+                    };
+                    ::partial_struct::Validate::validate(&full).map_err(#error_ident::Invalid)?;
+                    ::core::result::Result::Ok(full)
+                }
+            }
+        };
+        (error_enum, impls)
+    } else {
+        (quote! {}, quote! {})
+    };
+
+    let positional_fields: Vec<(&Field, FieldKind)> = field_kinds
+        .iter()
+        .filter(|(.., kind)| *kind != FieldKind::Omitted)
+        .flat_map(|&(field, _, name, kind)| match flatten_of(name) {
+            Some(flatten) => flatten.fields.iter().map(|inner| (inner, kind)).collect(),
+            None => vec![(field, kind)],
+        })
+        .collect();
+    // Same higher-ranked bounds as `changed`, so a field type without
+    // `Debug` only makes `for_each_field` unusable.
+    let for_each_field_doc = "Calls `f` with the name and value of every field of this partial struct, in declaration order, for logging any partial generically.";
+    let for_each_field_types: Vec<_> = positional_fields
+        .iter()
+        .map(|&(field, kind)| match kind {
+            FieldKind::Optional => repr_of(field).field_ty(),
+            _ => included_ty(field),
+        })
+        .collect();
+    let for_each_field_names = positional_fields.iter().map(|(field, _)| {
+        field
+            .ident
+            .as_ref()
+            .map(|id| id.unraw().to_string())
+            .unwrap_or_default()
+    });
+    let for_each_field_idents = positional_fields.iter().map(|(field, _)| &field.ident);
+    let for_each_field_body = if positional_fields.is_empty() {
+        quote! { let _ = &mut f; }
+    } else {
+        quote! { #( f(#for_each_field_names, &self.#for_each_field_idents); )* }
+    };
+    let for_each_field_tokens = quote! {
+        #[doc = #for_each_field_doc]
+        pub fn for_each_field<__PartialFn: FnMut(&'static str, &dyn ::core::fmt::Debug)>(&self, mut f: __PartialFn)
+        where
+            #( for<'__partial> #for_each_field_types: ::core::fmt::Debug, )*
+        {
+            #for_each_field_body
+        }
+    };
+    // A string map round-trip for dynamic forms. The bounds are higher-ranked
+    // like `changed`, so a field type without `Display` or `FromStr` only
+    // makes the method using it unusable.
+    let string_map_tokens = if partial_args.string_map {
+        let to_string_map_doc = "Returns every field as its `Display`-formatted value keyed by field name, leaving out optional fields that are `None` (for example to fill an HTML form).";
+        let from_string_map_doc = "Parses every field with `FromStr` from the value stored under its name, the reverse of `to_string_map`. Optional fields may be absent; a missing or unparsable kept field is an error naming it.";
+        let string_map_types: Vec<_> = positional_fields
+            .iter()
+            .map(|(field, _)| &field.ty)
+            .collect();
+        let to_string_map_statements = positional_fields.iter().map(|&(field, kind)| {
+            let ident = &field.ident;
+            let name = ident.as_ref().map(|id| id.unraw().to_string()).unwrap_or_default();
+            if kind == FieldKind::Optional {
+                let value = repr_of(field).borrow(quote! { self.#ident });
+                quote! {
+                    if let Some(value) = #value {
+                        __partial_map.insert(::std::string::String::from(#name), ::std::string::ToString::to_string(value));
+                    }
+                }
+            } else {
+                let value = borrow_included(quote! { self.#ident });
+                quote! { __partial_map.insert(::std::string::String::from(#name), ::std::string::ToString::to_string(#value)); }
+            }
+        });
+        // The parsed values live in `__partial_value_<field>` locals, so a field
+        // named like the map parameter cannot shadow it.
+        let string_map_local = |field: &Field| {
+            let name = field
+                .ident
+                .as_ref()
+                .map(|id| id.unraw().to_string())
+                .unwrap_or_default();
+            Ident::new(&format!("__partial_value_{}", name), Span::call_site())
+        };
+        let from_string_map_statements = positional_fields.iter().map(|&(field, kind)| {
+            let ident = &field.ident;
+            let ty = &field.ty;
+            let name = ident.as_ref().map(|id| id.unraw().to_string()).unwrap_or_default();
+            let local = string_map_local(field);
+            let parsed = quote! {
+                <#ty as ::core::str::FromStr>::from_str(value)
+                    .map_err(|err| ::std::format!("field `{}`: {}", #name, err))?
+            };
+            if kind == FieldKind::Optional {
+                quote! {
+                    let #local = match __partial_map.get(#name) {
+                        Some(value) => Some(#parsed),
+                        None => None,
+                    };
+                }
+            } else {
+                quote! {
+                    let #local = match __partial_map.get(#name) {
+                        Some(value) => #parsed,
+                        None => return ::core::result::Result::Err(::std::format!("missing field `{}`", #name)),
+                    };
+                }
+            }
+        });
+        let from_string_map_assignments = positional_fields.iter().map(|&(field, kind)| {
+            let ident = &field.ident;
+            let local = string_map_local(field);
+            let value = if kind == FieldKind::Optional {
+                repr_of(field).wrap_option(quote! { #local })
+            } else {
+                wrap_included(quote! { #local })
+            };
+            quote! { #ident: #value }
+        });
+        quote! {
+            #[doc = #to_string_map_doc]
+            pub fn to_string_map(&self) -> ::std::collections::HashMap<::std::string::String, ::std::string::String>
+            where
+                #( for<'__partial> #string_map_types: ::core::fmt::Display, )*
+            {
+                #[allow(unused_mut)]
+                let mut __partial_map = ::std::collections::HashMap::new();
+                #( #to_string_map_statements )*
+                __partial_map
+            }
+
+            #[doc = #from_string_map_doc]
+            pub fn from_string_map(
+                __partial_map: &::std::collections::HashMap<::std::string::String, ::std::string::String>,
+            ) -> ::core::result::Result<Self, ::std::string::String>
+            where
+                #( for<'__partial> #string_map_types: ::core::str::FromStr, )*
+                #( for<'__partial> <#string_map_types as ::core::str::FromStr>::Err: ::core::fmt::Display, )*
+            {
+                #( #from_string_map_statements )*
+                ::core::result::Result::Ok(Self {
+                    #( #from_string_map_assignments, )*
+                })
+            }
+        }
+    } else {
+        quote! {}
+    };
+    let (tuple_struct_tokens, tuple_impl_tokens) = if partial_args.tuple_struct {
+        let tuple_ident = Ident::new(&format!("{}Tuple", target_ident), Span::call_site());
+        let tuple_struct_doc = format!(
+            "The fields of `{}` in declaration order, as a tuple struct.",
+            target_ident
+        );
+        let tuple_from_doc = "Converts this partial struct into its positional tuple-struct form.";
+        let tuple_types = positional_fields.iter().map(|&(field, kind)| {
+            let ty = &field.ty;
+            if kind == FieldKind::Optional {
+                let field_ty = repr_of(field).field_ty();
+                quote! { pub #field_ty }
+            } else {
+                quote! { pub #ty }
+            }
+        });
+        let tuple_idents = positional_fields.iter().map(|(field, _)| &field.ident);
+        let tuple_struct = quote! {
+            #[doc = #tuple_struct_doc]
+            #derives
+            #dead_code_attr
+            #vis struct #tuple_ident #partial_generics ( #(#tuple_types),* ) #partial_where_clause;
+        };
+        let tuple_impls = quote! {
+            #[doc = #tuple_from_doc]
+            impl #partial_impl_generics From<#target_ident #partial_ty_generics> for #tuple_ident #partial_ty_generics #partial_where_clause {
+                #[inline]
+                fn from(partial: #target_ident #partial_ty_generics) -> Self {
+                    Self( #(partial.#tuple_idents),* )
+                }
+            }
+
+            impl #partial_impl_generics #target_ident #partial_ty_generics #partial_where_clause {
+                #[doc = #tuple_from_doc]
+                #[inline]
+                pub fn into_tuple_struct(self) -> #tuple_ident #partial_ty_generics {
+                    self.into()
+                }
+            }
+        };
+        (tuple_struct, tuple_impls)
+    } else {
+        (quote! {}, quote! {})
+    };
+
+    let constructor_tokens = if partial_args.constructor {
+        let constructor_doc = "Builds the partial struct from its fields in declaration order. Optional fields are passed as `Option`.";
+        let params = positional_fields.iter().map(|&(field, kind)| {
+            let ident = &field.ident;
+            let ty = &field.ty;
+            if kind == FieldKind::Optional {
+                quote! { #ident: Option<#ty> }
+            } else {
+                quote! { #ident: #ty }
+            }
+        });
+        let assignments = positional_fields.iter().map(|&(field, kind)| {
+            let ident = &field.ident;
+            if kind == FieldKind::Optional {
+                let value = repr_of(field).wrap_option(quote! { #ident });
+                quote! { #ident: #value }
+            } else {
+                let value = wrap_included(quote! { #ident });
+                quote! { #ident: #value }
+            }
+        });
+        quote! {
+            impl #partial_impl_generics #target_ident #partial_ty_generics #partial_where_clause {
+                #[doc = #constructor_doc]
+                #[inline]
+                pub fn new( #(#params),* ) -> Self {
+                    Self {
+                        #(#assignments,)*
+                    }
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    // Unlike `new`, `from_parts` takes every field exactly as it is stored,
+    // so a `via` field is passed as its wrapper.
+    let from_parts_doc = "Builds the partial struct from its fields in declaration order, each of the type it is stored as (optional fields as their `Option` or wrapper).";
+    let from_parts_params = positional_fields.iter().map(|&(field, kind)| {
+        let ident = &field.ident;
+        let ty = match kind {
+            FieldKind::Optional => repr_of(field).field_ty(),
+            _ => included_ty(field),
+        };
+        quote! { #ident: #ty }
+    });
+    let from_parts_idents = positional_fields.iter().map(|(field, _)| &field.ident);
+    let from_parts_tokens = quote! {
+        #[doc = #from_parts_doc]
+        #[inline]
+        #[allow(clippy::too_many_arguments)]
+        pub fn from_parts( #(#from_parts_params),* ) -> Self {
+            Self {
+                #(#from_parts_idents,)*
+            }
+        }
+    };
+
+    let (frozen_struct_tokens, frozen_impl_tokens) = if partial_args.frozen {
+        let frozen_ident = Ident::new(&format!("Frozen{}", target_ident), Span::call_site());
+        let frozen_struct_doc = format!(
+            "A read-only `{}`: its fields can be read through getters but never changed.",
+            target_ident
+        );
+        let frozen_from_doc = "Freezes a partial struct so it can no longer be modified.";
+        let freeze_doc =
+            "Moves this partial struct into an `Arc`, so it can be shared but no longer modified.";
+        let into_frozen_doc = format!(
+            "Wraps this partial struct in a read-only `{}`.",
+            frozen_ident
+        );
+        let into_inner_doc = "Unwraps the partial struct, making it modifiable again.";
+        let included_getters = included_fields.iter().map(|field| {
+            let ident = &field.ident;
+            let ty = &field.ty;
+            let get = borrow_included(quote! { self.0.#ident });
+            let get_doc = format!(
+                "Borrows `{}`.",
+                ident
+                    .as_ref()
+                    .map(|id| id.unraw().to_string())
+                    .unwrap_or_default()
+            );
+            quote! {
+                #[doc = #get_doc]
+                #[inline]
+                pub fn #ident(&self) -> &#ty {
+                    #get
+                }
+            }
+        });
+        let optional_getters = optional_fields.iter().map(|field| {
+            let ident = &field.ident;
+            let ty = &field.ty;
+            let get_doc = format!(
+                "Borrows `{}` if it is set.",
+                ident
+                    .as_ref()
+                    .map(|id| id.unraw().to_string())
+                    .unwrap_or_default()
+            );
+            let get = repr_of(field).borrow(quote! { self.0.#ident });
+            quote! {
+                #[doc = #get_doc]
+                #[inline]
+                pub fn #ident(&self) -> Option<&#ty> {
+                    #get
+                }
+            }
+        });
+        let frozen_struct = quote! {
+            #[doc = #frozen_struct_doc]
+            #derives
+            #dead_code_attr
+            #vis struct #frozen_ident #partial_generics (#target_ident #partial_ty_generics) #partial_where_clause;
+        };
+        let frozen_impls = quote! {
+            #[doc = #frozen_from_doc]
+            impl #partial_impl_generics From<#target_ident #partial_ty_generics> for #frozen_ident #partial_ty_generics #partial_where_clause {
+                #[inline]
+                fn from(partial: #target_ident #partial_ty_generics) -> Self {
+                    Self(partial)
+                }
+            }
+
+            impl #partial_impl_generics #frozen_ident #partial_ty_generics #partial_where_clause {
+                #(#included_getters)*
+                #(#optional_getters)*
+
+                #[doc = #into_inner_doc]
+                #[inline]
+                pub fn into_inner(self) -> #target_ident #partial_ty_generics {
+                    self.0
+                }
+            }
+
+            impl #partial_impl_generics #target_ident #partial_ty_generics #partial_where_clause {
+                #[doc = #into_frozen_doc]
+                #[inline]
+                pub fn into_frozen(self) -> #frozen_ident #partial_ty_generics {
+                    #frozen_ident(self)
+                }
+
+                #[doc = #freeze_doc]
+                #[inline]
+                pub fn freeze(self) -> ::std::sync::Arc<Self> {
+                    ::std::sync::Arc::new(self)
+                }
+            }
+        };
+        (frozen_struct, frozen_impls)
+    } else {
+        (quote! {}, quote! {})
+    };
+
+    // Missing and `null` keys both leave optional fields unset.
+    let from_json_tokens = if partial_args.from_json {
+        let from_json_doc = "Reads the partial struct from a JSON object, one key per field. Optional fields may be missing or `null`.";
+        let json_types = included_fields
+            .iter()
+            .chain(optional_fields.iter())
+            .map(|field| &field.ty);
+        let included_reads = included_fields.iter().map(|field| {
+            let ident = &field.ident;
+            let ty = &field.ty;
+            let key = ident.as_ref().map(|id| id.unraw().to_string()).unwrap_or_default();
+            quote! {
+                #ident: match object.get(#key) {
+                    Some(value) => ::serde_json::from_value::<#ty>(value.clone())
+                        .map_err(|err| ::std::format!("field `{}`: {}", #key, err))?,
+                    None => return ::core::result::Result::Err(::std::format!("missing field `{}`", #key)),
+                }
+            }
+        });
+        let optional_reads = optional_fields.iter().map(|field| {
+            let ident = &field.ident;
+            let ty = &field.ty;
+            let key = ident
+                .as_ref()
+                .map(|id| id.unraw().to_string())
+                .unwrap_or_default();
+            let value = repr_of(field).wrap_option(quote! {
+                match object.get(#key) {
+                    None | Some(::serde_json::Value::Null) => None,
+                    Some(value) => Some(
+                        ::serde_json::from_value::<#ty>(value.clone())
+                            .map_err(|err| ::std::format!("field `{}`: {}", #key, err))?,
+                    ),
+                }
+            });
+            quote! { #ident: #value }
+        });
+        let target_name = target_ident.to_string();
+        quote! {
+            impl #partial_impl_generics #target_ident #partial_ty_generics
+            where
+                #( #partial_predicates, )*
+                #( #json_types: ::serde::de::DeserializeOwned, )*
+            {
+                #[doc = #from_json_doc]
+                pub fn from_json_value(value: &::serde_json::Value) -> ::core::result::Result<Self, ::std::string::String> {
+                    let object = value
+                        .as_object()
+                        .ok_or_else(|| ::std::format!("expected a JSON object for `{}`", #target_name))?;
+                    ::core::result::Result::Ok(Self {
+                        #(#included_reads,)*
+                        #(#optional_reads,)*
+                    })
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let items = quote! {
+        #[doc = #struct_doc]
+        #[doc = ""]
+        #[doc = #usage_doc]
+        #repr_doc
+        #derives
+        #(#repr_attrs)*
+        #(#struct_attrs)*
+        #dead_code_attr
+        #vis struct #target_ident #partial_generics #partial_where_clause {
+            #(#included_fields_tokens,)*
+            #(#optional_fields_tokens,)*
+        }
+
+        #omitted_struct_tokens
+
+        #borrowed_struct_tokens
+
+        #tuple_struct_tokens
+
+        #frozen_struct_tokens
+
+        #view_struct_tokens
+
+        #field_enum_tokens
+
+        #try_complete_struct_tokens
+
+        #try_rebuild_enum_tokens
+        #freeze_struct_tokens
+    };
+
+    let impls = quote! {
+        #ordering_tokens
+
+        #clone_tokens
+
+        impl #partial_impl_generics #target_ident #partial_ty_generics #partial_where_clause {
+            #[doc = #consuming_method_doc]
+            #must_use_attr
+            #[inline]
+            pub fn #method_ident #method_generics (self, #( #to_method_params ),* ) -> #orig_name #orig_ty_generics
+            where
+                #( #orig_predicates, )*
+            {
+                #orig_name {
+                    #construction_assignments
+                }
+            }
+
+            #[doc = #cloned_method_doc1]
+            #[doc = #cloned_method_doc2]
+            #must_use_attr
+            #[inline]
+            pub fn #cloned_method_ident #method_generics (&self, #( #to_method_params ),* ) -> #orig_name #orig_ty_generics
+            where
+                #( #orig_predicates, )*
+                #( #included_field_types: Clone, )*
+                #( #optional_field_types: Clone, )*
+            {
+                #orig_name {
+                    #cloned_construction_assignments
+                }
+            }
+
+            #with_method_tokens
+
+            #[doc = #or_current_doc]
+            #[inline]
+            pub fn #or_current_method_ident #method_generics (self, current: #orig_name #orig_ty_generics) -> #orig_name #orig_ty_generics
+            where
+                #( #orig_predicates, )*
+            {
+                #orig_name {
+                    #or_current_assignments
+                }
+            }
+
+            #defaulted_tokens
+
+            #[doc = #from_base_doc]
+            #[inline]
+            pub fn #from_base_method_ident #method_generics (self, base: #orig_name #orig_ty_generics) -> #orig_name #orig_ty_generics
+            where
+                #( #orig_predicates, )*
+            {
+                self.#or_current_method_ident(base)
+            }
+
+            #[doc = #merge_into_doc]
+            #[inline]
+            pub fn merge_into #method_generics (self, target: &mut #orig_name #orig_ty_generics)
+            where
+                #( #orig_predicates, )*
+            {
+                #( #merge_into_statements )*
+            }
+
+            #[doc = #diff_doc]
+            pub fn diff #method_generics (&self, full: &#orig_name #orig_ty_generics) -> ::std::vec::Vec<&'static str>
+            where
+                #( #orig_predicates, )*
+                #( for<'__partial> #diff_types: ::core::cmp::PartialEq, )*
+            {
+                #diff_body
+            }
+
+            #[doc = #from_with_omitted_doc]
+            #[inline]
+            pub fn #from_with_omitted_ident #method_generics (full: #orig_name #orig_ty_generics) -> (Self, #omitted_struct_ty)
+            where
+                #( #orig_predicates, )*
+            {
+                let #orig_name { #(#field_idents,)* } = full;
+                (
+                    Self {
+                        #(#partial_from_full_assignments,)*
+                    },
+                    #omitted_struct_ctor,
+                )
+            }
+
+            #take_omitted_tokens
+
+            #omitted_defaults_tokens
+
+            #[doc = #fold_doc]
+            #[inline]
+            pub fn fold_set_optionals<__PartialAcc>(&self, init: __PartialAcc, f: impl FnMut(__PartialAcc, &'static str) -> __PartialAcc) -> __PartialAcc {
+                #fold_body
+            }
+
+            #[doc = #present_optionals_doc]
+            pub fn present_optionals(&self) -> ::std::vec::Vec<&'static str> {
+                self.fold_set_optionals(::std::vec::Vec::new(), |mut present, name| {
+                    present.push(name);
+                    present
+                })
+            }
+
+            #[doc = #absent_optionals_doc]
+            pub fn absent_optionals(&self) -> ::std::vec::Vec<&'static str> {
+                #absent_body
+            }
+
+            #[doc = #changed_doc]
+            pub fn changed(&self) -> ::std::vec::Vec<(&'static str, ::std::string::String)>
+            where
+                #( for<'__partial> #changed_types: ::core::fmt::Display, )*
+            {
+                #changed_body
+            }
+
+            #for_each_field_tokens
+
+            #string_map_tokens
+
+            #from_parts_tokens
+
+            #[doc = #apply_if_doc]
+            #[inline]
+            pub fn apply_if(self, cond: bool, f: impl FnOnce(Self) -> Self) -> Self {
+                if cond {
+                    f(self)
+                } else {
+                    self
+                }
+            }
+
+            #[doc = #source_struct_doc]
+            pub const SOURCE_STRUCT: &'static str = #source_struct_name;
+
+            #[doc = #included_names_doc]
+            #[inline]
+            pub const fn included_field_names() -> &'static [&'static str] {
+                &[#(#included_name_strs),*]
+            }
+
+            #[doc = #omitted_names_doc]
+            #[inline]
+            pub const fn omitted_field_names() -> &'static [&'static str] {
+                &[#(#omitted_name_strs),*]
+            }
+
+            #[doc = #schema_doc]
+            pub fn schema() -> Vec<::partial_struct::FieldSchema> {
+                ::std::vec![#(#schema_entries),*]
+            }
+        }
+
+        #from_impl_tokens
+
+        #cloned_from_tokens
+
+        #borrowed_impl_tokens
+
+        #tuple_impl_tokens
+
+        #try_complete_impl_tokens
+
+        #try_rebuild_tokens
+        #freeze_impl_tokens
+
+        #empty_tokens
+
+        #accessor_tokens
+
+        #constructor_tokens
+
+        #frozen_impl_tokens
+
+        #redact_tokens
+
+        #omitted_into_tokens
+
+        #view_impl_tokens
+
+        #layout_assert_tokens
+
+        #field_enum_impl_tokens
+
+        #from_json_tokens
+
+        #(#maps_to_tokens)*
+
+        #(#structural_from_tokens)*
+
+        #cross_eq_tokens
+
+        #log_missing_tokens
+
+        #complete_tokens
+
+        #hash_helper_tokens
+
+        #overlay_tokens
+
+        impl #orig_impl_generics #orig_name #orig_ty_generics #orig_where_clause {
+            #[doc = #into_with_omitted_doc]
+            #[inline]
+            pub fn #into_with_omitted_ident(self) -> (#target_ident #partial_ty_generics, #omitted_struct_ty) {
+                #target_ident::#from_with_omitted_ident(self)
+            }
+
+            #[doc = #apply_partial_doc]
+            #[inline]
+            pub fn #apply_partial_ident(&mut self, partial: #target_ident #partial_ty_generics) {
+                partial.merge_into(self)
+            }
+        }
+    };
+
+    let generated =
+        if partial_args.scoped_impls || has_deprecated_fields || partial_args.allow_dead_code {
+            // Moving deprecated fields around is the point of these impls, so
+            // the lint is silenced for the whole block.
+            let allow_deprecated = if has_deprecated_fields {
+                quote! { #[allow(deprecated)] }
+            } else {
+                quote! {}
+            };
+            quote! {
+                #items
+
+                #allow_deprecated
+                #dead_code_attr
+                const _: () = {
+                    #impls
+                };
+            }
+        } else {
+            quote! {
+                #items
+
+                #impls
+            }
+        };
+
+    // The module sees the parent's items, including the original struct
+    // and the field types, through the glob import.
+    let (tokens, path) = match &partial_args.module {
+        Some(module) => {
+            let module_doc = format!(
+                "Items generated for `{}` from `{}`.",
+                target_ident, orig_name
+            );
+            let tokens = quote! {
+                #[doc = #module_doc]
+                pub mod #module {
+                    #[allow(unused_imports)]
+                    use super::*;
+
+                    #generated
+                }
+            };
+            (tokens, quote! { #module::#target_ident })
+        }
+        None => (generated, quote! { #target_ident }),
+    };
+
+    let route = PartialRoute {
+        name: target_name_str.to_snake_case(),
+        doc_path: path.to_string().replace(' ', ""),
+        header: quote! { #dead_code_attr impl #partial_impl_generics #path #partial_ty_generics #partial_where_clause },
+        ty: quote! { #path #partial_ty_generics },
+        vis: partial_args.vis.clone().unwrap_or_else(|| ast.vis.clone()),
+        method_generics,
+        rebuild: method_ident,
+        params: to_method_params,
+        args: omitted_fields
+            .iter()
+            .chain(fallback_fields.iter())
+            .filter_map(|field| field.ident.clone())
+            .collect(),
+        predicates: orig_predicates
+            .iter()
+            .map(|predicate| quote! { #predicate })
+            .collect(),
+        from_full: !partial_args.skip_from,
+    };
+    Ok(GeneratedPartial { tokens, route })
+}
+
+/// The output of [`generate_partial`], with what other partials of the same
+/// struct need to convert into this one.
+struct GeneratedPartial {
+    tokens: TokenStream2,
+    route: PartialRoute,
+}
+
+/// How a generated partial is rebuilt into the full struct and, when it
+/// implements `From` the full struct, reached from it.
+struct PartialRoute {
+    /// The partial's name in snake_case, as used in `into_<name>`.
+    name: String,
+    /// The partial's path from the derive's call site, for doc links.
+    doc_path: String,
+    /// `impl<..> Partial<..> where ..`, opening an inherent impl block.
+    header: TokenStream2,
+    ty: TokenStream2,
+    vis: syn::Visibility,
+    method_generics: TokenStream2,
+    /// The `to_<orig>` method and the parameters it takes.
+    rebuild: Ident,
+    params: Vec<TokenStream2>,
+    args: Vec<Ident>,
+    /// The full struct's where predicates, with this partial's `where_bound(...)`.
+    predicates: Vec<TokenStream2>,
+    from_full: bool,
+}
+
+/// Generates `into_<partial>` on the partial `from` for each partial in
+/// `to`, rebuilding the full struct in between.
+fn partial_conversions(
+    ast: &DeriveInput,
+    from: &PartialRoute,
+    to: &[&PartialRoute],
+) -> TokenStream2 {
+    if to.is_empty() {
+        return quote! {};
+    }
+    let orig_name = &ast.ident;
+    let (_, orig_ty_generics, _) = ast.generics.split_for_impl();
+    let PartialRoute {
+        header,
+        method_generics,
+        rebuild,
+        params,
+        args,
+        predicates,
+        ..
+    } = from;
+    let methods = to.iter().map(|to| {
+        let PartialRoute { ty: to_ty, vis, .. } = to;
+        let method_ident = Ident::new(&format!("into_{}", to.name), Span::call_site());
+        let doc = format!(
+            "Converts this partial into a [`{}`] by rebuilding the full `{}` with [`Self::{}`] and projecting it.",
+            to.doc_path,
+            orig_name.unraw(),
+            rebuild
+        );
+        // `where_bound(...)` predicates of the target are needed for its `From` impl.
+        let own: HashSet<String> = predicates.iter().map(|predicate| predicate.to_string()).collect();
+        let to_predicates = to.predicates.iter().filter(|predicate| !own.contains(&predicate.to_string()));
+        quote! {
+            #[doc = #doc]
+            #[inline]
+            #vis fn #method_ident #method_generics (self, #( #params ),* ) -> #to_ty
+            where
+                #( #predicates, )*
+                #( #to_predicates, )*
+            {
+                <#to_ty as ::core::convert::From<#orig_name #orig_ty_generics>>::from(self.#rebuild( #( #args ),* ))
+            }
+        }
+    });
+    quote! {
+        #header {
+            #(#methods)*
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        expand, generate_partial, split_field_defaults, type_matches, PartialArgs, StructInfo,
+        PARTIAL_OPTIONS,
+    };
+    use quote::ToTokens;
+
+    /// Expands `input` and parses the result back into items.
+    fn expand_to_file(input: &syn::DeriveInput) -> syn::File {
+        syn::parse2(expand(input, Default::default())).expect("expansion should parse")
+    }
+
+    /// The generated struct called `name`.
+    fn struct_named<'a>(items: &'a [syn::Item], name: &str) -> &'a syn::ItemStruct {
+        items
+            .iter()
+            .find_map(|item| match item {
+                syn::Item::Struct(item) if item.ident == name => Some(item),
+                _ => None,
+            })
+            .unwrap_or_else(|| panic!("{} should be generated", name))
+    }
+
+    /// The `#[doc]` strings on `item`, in order.
+    fn doc_lines(item: &syn::ItemStruct) -> Vec<String> {
+        item.attrs
+            .iter()
+            .filter_map(|attr| match &attr.meta {
+                syn::Meta::NameValue(doc) if doc.path.is_ident("doc") => {
+                    Some(doc.value.to_token_stream().to_string())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Compares the pretty-printed expansion of a struct exercising most
+    /// options against `tests/expand/representative.expanded.rs`. Run with
+    /// `UPDATE_EXPANSION=1` to rewrite the snapshot after an intended change.
+    #[test]
+    fn representative_expansion_is_unchanged() {
+        let input = syn::parse_quote! {
+            #[partial("Draft", derive(Debug, Clone, PartialEq), omit(id, secret), optional(nickname, score = 0), borrowed, cross_eq, hash_helper)]
+            #[partial("Summary", derive(Debug, PartialEq, Eq, PartialOrd, Ord), omit(tags, secret, score), from_shared, tuple_struct, try_complete)]
+            #[partial("Patch", all_optional, omit(id), rebuild_fn = "apply_to", scoped_impls)]
+            #[partial("Projection", omit(secret), maps_to = "OtherUser<'a, T, N>")]
+            pub struct User<'a, T: Clone, const N: usize>
+            where
+                T: Default,
+            {
+                id: u64,
+                #[partial(sort_key)]
+                r#type: &'a str,
+                nickname: String,
+                score: u32,
+                tags: [T; N],
+                secret: Vec<u8>,
+            }
+        };
+        let expanded = expand_to_file(&input);
+        let actual = prettyplease::unparse(&expanded);
+
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/expand/representative.expanded.rs"
+        );
+        if std::env::var_os("UPDATE_EXPANSION").is_some() {
+            std::fs::write(path, &actual).unwrap();
+        }
+        let expected = std::fs::read_to_string(path).unwrap();
+        assert!(
+            actual == expected,
+            "expansion changed; rerun with UPDATE_EXPANSION=1 if intended"
+        );
+    }
+
+    #[test]
+    fn forward_attrs_keeps_listed_and_deprecated_attributes() {
+        let input = syn::parse_quote! {
+            #[partial(omit(id), forward_attrs(serde))]
+            struct User {
+                id: u32,
+                #[deprecated = "use `name`"]
+                #[serde(rename = "nick")]
+                #[validate(length(min = 1))]
+                nickname: String,
+            }
+        };
+        let expanded = expand_to_file(&input);
+        let partial = struct_named(&expanded.items, "PartialUser");
+        let field = partial
+            .fields
+            .iter()
+            .next()
+            .expect("nickname should be kept");
+        let paths: Vec<_> = field
+            .attrs
+            .iter()
+            .map(|attr| attr.path().get_ident().unwrap().to_string())
+            .collect();
+        assert_eq!(paths, ["deprecated", "serde"]);
+    }
+
+    #[test]
+    fn forward_attrs_carries_sqlx_column_renames() {
+        let input = syn::parse_quote! {
+            #[partial("UserRow", derive(sqlx::FromRow), omit(id), optional(email), forward_attrs(sqlx))]
+            struct User {
+                id: u32,
+                #[sqlx(rename = "user_name")]
+                #[serde(rename = "userName")]
+                name: String,
+                #[sqlx(rename = "email_address")]
+                email: String,
+            }
+        };
+        let expanded = expand_to_file(&input);
+        let attrs: Vec<_> = struct_named(&expanded.items, "UserRow")
+            .fields
+            .iter()
+            .map(|field| {
+                let attrs = field
+                    .attrs
+                    .iter()
+                    .map(|attr| attr.to_token_stream().to_string());
+                (
+                    field.ident.as_ref().unwrap().to_string(),
+                    attrs.collect::<Vec<_>>(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            attrs,
+            [
+                (
+                    "name".to_string(),
+                    vec!["# [sqlx (rename = \"user_name\")]".to_string()]
+                ),
+                (
+                    "email".to_string(),
+                    vec!["# [sqlx (rename = \"email_address\")]".to_string()]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn generated_types_mirror_the_original_visibility() {
+        let input = syn::parse_quote! {
+            #[partial(omit(id))]
+            #[partial("UserPatch", optional(name), vis = "pub")]
+            #[partial("UserDraft", omit(id), module = "draft")]
+            pub(crate) struct User {
+                id: u32,
+                name: String,
+            }
+        };
+        let expanded = expand_to_file(&input);
+        let vis_of = |items: &[syn::Item], name: &str| {
+            struct_named(items, name).vis.to_token_stream().to_string()
+        };
+        assert_eq!(vis_of(&expanded.items, "PartialUser"), "pub (crate)");
+        assert_eq!(vis_of(&expanded.items, "PartialUserOmitted"), "pub (crate)");
+        assert_eq!(vis_of(&expanded.items, "UserPatch"), "pub");
+        let module = expanded
+            .items
+            .iter()
+            .find_map(|item| match item {
+                syn::Item::Mod(item) if item.ident == "draft" => item.content.as_ref(),
+                _ => None,
+            })
+            .expect("the draft module should be generated");
+        assert_eq!(vis_of(&module.1, "UserDraft"), "pub (crate)");
+    }
+
+    #[test]
+    fn struct_doc_lists_omitted_and_optional_fields() {
+        let input = syn::parse_quote! {
+            #[partial(omit(id), optional(email, phone))]
+            #[partial("UserForm", omit(id), forward_attrs(serde, validate))]
+            struct User {
+                id: u32,
+                name: String,
+                email: String,
+                phone: String,
+            }
+        };
+        let expanded = expand_to_file(&input);
+        assert_eq!(
+            doc_lines(struct_named(&expanded.items, "PartialUser"))[0],
+            "\"A partial version of `User` omitting the field(s): id; fields made optional: email, phone. Field attributes are copied.\""
+        );
+        let form_doc = &doc_lines(struct_named(&expanded.items, "UserForm"))[0];
+        assert!(
+            form_doc.ends_with("Of the field attributes, only doc comments, `deprecated` and `serde`, `validate` are copied.\""),
+            "{}",
+            form_doc
+        );
+    }
+
+    #[test]
+    fn struct_doc_names_the_split_and_rebuild_methods() {
+        let input = syn::parse_quote! {
+            #[partial(omit(id))]
+            #[partial("UserPatch", rebuild_fn = "apply_to")]
+            struct User {
+                id: u32,
+                name: String,
+            }
+        };
+        let expanded = expand_to_file(&input);
+        let doc_of = |name: &str| doc_lines(struct_named(&expanded.items, name)).join("\n");
+        let doc = doc_of("PartialUser");
+        assert!(
+            doc.contains("[`PartialUser::from_user_with_omitted`]"),
+            "{}",
+            doc
+        );
+        assert!(doc.contains("[`PartialUser::to_user`]"), "{}", doc);
+        let doc = doc_of("UserPatch");
+        assert!(doc.contains("[`UserPatch::apply_to`]"), "{}", doc);
+    }
+
+    #[test]
+    fn generate_partial_builds_one_partial_without_attributes() {
+        // The struct carries no `#[partial]` attribute: the arguments are
+        // handed to `generate_partial` directly.
+        let input = syn::parse_quote! {
+            pub struct Account {
+                id: u64,
+                email: String,
+                nickname: String,
+            }
+        };
+        let info =
+            StructInfo::new(&input, Default::default()).expect("named struct should be accepted");
+        let args: PartialArgs =
+            syn::parse_str("\"AccountDraft\", omit(id), optional(nickname)").unwrap();
+        let generated = generate_partial(&info, args).expect("partial should be generated");
+        assert_eq!(generated.route.name, "account_draft");
+        let expanded = syn::parse2::<syn::File>(generated.tokens).expect("partial should parse");
+        let fields: Vec<_> = struct_named(&expanded.items, "AccountDraft")
+            .fields
+            .iter()
+            .map(|field| {
+                format!(
+                    "{}: {}",
+                    field.ident.as_ref().unwrap(),
+                    field.ty.to_token_stream()
+                )
+            })
+            .collect();
+        assert_eq!(fields, ["email: String", "nickname: Option < String >"]);
+    }
+
+    #[test]
+    fn struct_info_rejects_tuple_structs() {
+        let input = syn::parse_quote! {
+            struct Pair(u8, u8);
+        };
+        let err = StructInfo::new(&input, Default::default())
+            .err()
+            .expect("tuple struct should be rejected");
+        assert_eq!(
+            err.to_string(),
+            "Partial can only be derived for structs with named fields"
+        );
+    }
+
+    #[test]
+    fn default_field_values_are_split_off_and_kept_on_included_fields() {
+        let input = quote::quote! {
+            #[partial("LimitsDraft", omit(id))]
+            struct Limits<I: Iterator<Item = u8>> {
+                id: u32,
+                #[serde(rename = "r")]
+                rate: u32 = 10,
+                source: Option<I>,
+                hook: fn(u8) -> u8 = identity::<u8>,
+                r#burst: (u8, u8) = (1, 2),
+            }
+        };
+        let (stripped, values) = split_field_defaults(input);
+        let mut values: Vec<_> = values
+            .into_iter()
+            .map(|(name, value)| (name, value.to_string()))
+            .collect();
+        values.sort();
+        assert_eq!(
+            values,
+            [
+                ("burst".to_string(), "(1 , 2)".to_string()),
+                ("hook".to_string(), "identity :: < u8 >".to_string()),
+                ("rate".to_string(), "10".to_string()),
+            ]
+        );
+
+        let ast: syn::DeriveInput = syn::parse2(stripped).expect("stripped struct should parse");
+        let fields: Vec<_> = match &ast.data {
+            syn::Data::Struct(data) => data
+                .fields
+                .iter()
+                .map(|field| field.ty.to_token_stream().to_string())
+                .collect(),
+            _ => unreachable!(),
+        };
+        assert_eq!(
+            fields,
+            ["u32", "u32", "Option < I >", "fn (u8) -> u8", "(u8 , u8)"]
+        );
+
+        // syn cannot parse default field values back, so look at the tokens.
+        let values = [("rate".to_string(), quote::quote! { 10 })]
+            .into_iter()
+            .collect();
+        let expanded = expand(&ast, values).to_string();
+        assert!(expanded.contains("pub rate : u32 = 10 ,"), "{}", expanded);
+    }
+
+    /// Every option listed in `PARTIAL_OPTIONS` must reach its own match
+    /// arm; the key alone may still fail for want of arguments.
+    #[test]
+    fn partial_options_are_all_recognised() {
+        for option in PARTIAL_OPTIONS {
+            if let Err(err) = syn::parse_str::<PartialArgs>(option) {
+                assert!(
+                    !err.to_string().starts_with("unknown partial option"),
+                    "`{}` is listed but not parsed: {}",
+                    option,
+                    err
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn partial_args_parse_in_any_order() {
+        let entries = [
+            "derive(Debug, Clone)",
+            "omit(id)",
+            "optional(email, role = \"Role::User\")",
+        ];
+        let orders = [
+            [0, 1, 2],
+            [0, 2, 1],
+            [1, 0, 2],
+            [1, 2, 0],
+            [2, 0, 1],
+            [2, 1, 0],
+        ];
+        for order in orders {
+            let listed = order.map(|index| entries[index]).join(", ");
+            for source in [
+                format!("\"Draft\", {}", listed),
+                format!("{}, \"Draft\",", listed),
+            ] {
+                let args: PartialArgs = syn::parse_str(&source)
+                    .unwrap_or_else(|err| panic!("`{}` should parse: {}", source, err));
+                assert_eq!(
+                    args.target_name.map(|name| name.value()).as_deref(),
+                    Some("Draft"),
+                    "{}",
+                    source
+                );
+                let derives: Vec<_> = args
+                    .derive_traits
+                    .iter()
+                    .map(|path| path.to_token_stream().to_string())
+                    .collect();
+                assert_eq!(derives, ["Debug", "Clone"], "{}", source);
+                assert_eq!(args.omit_fields, ["id"], "{}", source);
+                let optional: Vec<_> = args
+                    .optional_fields
+                    .iter()
+                    .map(|optional| (optional.ident.to_string(), optional.default.is_some()))
+                    .collect();
+                assert_eq!(
+                    optional,
+                    [("email".to_string(), false), ("role".to_string(), true)],
+                    "{}",
+                    source
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn partial_args_accept_entries_without_commas() {
+        let args: PartialArgs = syn::parse_str("omit(id) optional(email) derive(Debug)")
+            .unwrap_or_else(|err| panic!("entries without commas should parse: {}", err));
+        assert_eq!(args.omit_fields, ["id"]);
+        assert_eq!(args.optional_fields.len(), 1);
+        let derives: Vec<_> = args
+            .derive_traits
+            .iter()
+            .map(|path| path.to_token_stream().to_string())
+            .collect();
+        assert_eq!(derives, ["Debug"]);
+    }
+
+    #[test]
+    fn rebuild_methods_are_must_use_unless_opted_out() {
+        let input = syn::parse_quote! {
+            #[partial("Kept", omit(id))]
+            #[partial("Dropped", omit(id), must_use(false))]
+            struct Packet {
+                id: u32,
+                len: u16,
+            }
+        };
+        let expanded = expand_to_file(&input);
+        let must_use_methods = |target: &str| -> Vec<String> {
+            expanded
+                .items
+                .iter()
+                .filter_map(|item| match item {
+                    syn::Item::Impl(item)
+                        if item.trait_.is_none()
+                            && item.self_ty.to_token_stream().to_string() == target =>
+                    {
+                        Some(item)
+                    }
+                    _ => None,
+                })
+                .flat_map(|item| &item.items)
+                .filter_map(|item| match item {
+                    syn::ImplItem::Fn(method)
+                        if method
+                            .attrs
+                            .iter()
+                            .any(|attr| attr.path().is_ident("must_use")) =>
+                    {
+                        Some(method.sig.ident.to_string())
+                    }
+                    _ => None,
+                })
+                .collect()
+        };
+        assert_eq!(must_use_methods("Kept"), ["to_packet", "to_packet_cloned"]);
+        assert!(must_use_methods("Dropped").is_empty());
+    }
+
+    #[test]
+    fn keep_repr_copies_repr_only_when_requested() {
+        let input = syn::parse_quote! {
+            #[repr(C)]
+            #[partial("Kept", omit(id), keep_repr)]
+            #[partial("Plain", omit(id))]
+            struct Packet {
+                id: u32,
+                len: u16,
+            }
+        };
+        let expanded = expand_to_file(&input);
+        let has_repr = |name: &str| {
+            struct_named(&expanded.items, name)
+                .attrs
+                .iter()
+                .any(|attr| attr.path().is_ident("repr"))
+        };
+        assert!(has_repr("Kept"));
+        assert!(!has_repr("Plain"));
+    }
+
+    #[test]
+    fn scoped_impls_move_every_impl_into_an_anonymous_const() {
+        let expand_with = |scoped: bool| {
+            let scoped = if scoped {
+                quote::quote!(scoped_impls)
+            } else {
+                quote::quote!()
+            };
+            let input = syn::parse_quote! {
+                #[partial("Form", omit(id), optional(email), cross_eq, #scoped)]
+                pub struct Account {
+                    id: u32,
+                    email: String,
+                }
+            };
+            expand_to_file(&input)
+        };
+        let top_level_impls = |file: &syn::File| {
+            file.items
+                .iter()
+                .filter(|item| matches!(item, syn::Item::Impl(_)))
+                .count()
+        };
+        let scoped_impls = |file: &syn::File| {
+            file.items
+                .iter()
+                .filter_map(|item| match item {
+                    syn::Item::Const(item) if item.ident == "_" => match &*item.expr {
+                        syn::Expr::Block(block) => Some(
+                            block
+                                .block
+                                .stmts
+                                .iter()
+                                .filter(|stmt| matches!(stmt, syn::Stmt::Item(syn::Item::Impl(_))))
+                                .count(),
+                        ),
+                        _ => None,
+                    },
+                    _ => None,
+                })
+                .sum::<usize>()
+        };
+
+        let plain = expand_with(false);
+        assert!(top_level_impls(&plain) > 0);
+        assert_eq!(scoped_impls(&plain), 0);
+
+        let scoped = expand_with(true);
+        assert_eq!(top_level_impls(&scoped), 0);
+        assert_eq!(scoped_impls(&scoped), top_level_impls(&plain));
+    }
+
+    #[test]
+    fn omit_type_matches_bare_paths_loosely() {
+        let matches = |ty: syn::Type, listed: syn::Type| type_matches(&ty, &listed);
+        assert!(matches(syn::parse_quote!(u8), syn::parse_quote!(u8)));
+        assert!(!matches(syn::parse_quote!(u16), syn::parse_quote!(u8)));
+        assert!(matches(
+            syn::parse_quote!(std::marker::PhantomData<T>),
+            syn::parse_quote!(PhantomData)
+        ));
+        assert!(matches(
+            syn::parse_quote!(Vec<u8>),
+            syn::parse_quote!(Vec<u8>)
+        ));
+        assert!(!matches(
+            syn::parse_quote!(Vec<u16>),
+            syn::parse_quote!(Vec<u8>)
+        ));
+        assert!(!matches(
+            syn::parse_quote!(marker::PhantomData<T>),
+            syn::parse_quote!(std::marker::PhantomData)
+        ));
+    }
+
+    /// Times the expansion of a 40-field struct with four partials. Run with
+    /// `cargo test -p partial_struct_derive --release -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn wide_struct_expansion_time() {
+        let fields = (0..40u32).map(|i| quote::format_ident!("field_{}", i));
+        let input: syn::DeriveInput = syn::parse_quote! {
+            #[partial("Draft", derive(Debug, Clone, PartialEq), omit(field_0, field_1), optional(field_2, field_3), borrowed, cross_eq)]
+            #[partial("Summary", derive(Debug, Clone), omit(field_10, field_11), from_shared, tuple_struct)]
+            #[partial("Patch", all_optional, omit(field_0))]
+            #[partial("Keyed", derive(Debug), optional(field_20, field_21 = 0), hash_helper)]
+            pub struct Wide {
+                #( #fields: u64, )*
+            }
+        };
+        let runs = 100;
+        let start = std::time::Instant::now();
+        for _ in 0..runs {
+            std::hint::black_box(expand(&input, Default::default()));
+        }
+        eprintln!("wide struct: {:?} per expansion", start.elapsed() / runs);
+    }
+}
@@ -0,0 +1,2003 @@
+///A partial version of `User` omitting the field(s): id, secret; fields made optional: nickname, score. Field attributes are copied.
+///
+///Split a `User` with [`Draft::from_user_with_omitted`] and rebuild it with [`Draft::to_user`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Draft<'a, T: Clone, const N: usize>
+where
+    T: Default,
+{
+    pub r#type: &'a str,
+    pub tags: [T; N],
+    pub nickname: Option<String>,
+    pub score: Option<u32>,
+}
+///Fields omitted from `User` when projecting into `Draft`.
+pub struct DraftOmitted {
+    pub id: u64,
+    pub secret: Vec<u8>,
+}
+///A borrowed view of `Draft`.
+pub struct DraftRef<'__partial, 'a, T: Clone, const N: usize>
+where
+    T: Default,
+{
+    pub r#type: &'__partial &'a str,
+    pub tags: &'__partial [T; N],
+    pub nickname: Option<&'__partial String>,
+    pub score: Option<&'__partial u32>,
+}
+impl<'a, T: Clone, const N: usize> Draft<'a, T, N>
+where
+    T: Default,
+{
+    ///Converts this partial struct into the full struct by providing the omitted fields.
+    #[must_use]
+    #[inline]
+    pub fn to_user(
+        self,
+        id: u64,
+        secret: Vec<u8>,
+        nickname: Option<String>,
+    ) -> User<'a, T, N>
+    where
+        T: Default,
+    {
+        User {
+            id: id,
+            r#type: self.r#type,
+            nickname: self
+                .nickname
+                .or(nickname)
+                .expect("Optional field must be provided"),
+            score: self.score.unwrap_or_else(|| 0),
+            tags: self.tags,
+            secret: secret,
+        }
+    }
+    ///Creates a new full struct by cloning the fields from this partial struct and providing the omitted fields.
+    ///Requires that all included fields implement `Clone`.
+    #[must_use]
+    #[inline]
+    pub fn to_user_cloned(
+        &self,
+        id: u64,
+        secret: Vec<u8>,
+        nickname: Option<String>,
+    ) -> User<'a, T, N>
+    where
+        T: Default,
+        &'a str: Clone,
+        [T; N]: Clone,
+        Option<String>: Clone,
+        Option<u32>: Clone,
+    {
+        User {
+            id: id,
+            r#type: self.r#type.clone(),
+            nickname: self
+                .nickname
+                .clone()
+                .or(nickname)
+                .expect("Optional field must be provided"),
+            score: self.score.clone().unwrap_or_else(|| 0),
+            tags: self.tags.clone(),
+            secret: secret,
+        }
+    }
+    ///Converts this partial struct into the full struct, calling the given closure for the omitted fields as a tuple in declaration order. The closure only runs here, so costly values are computed only when the full struct is rebuilt.
+    #[inline]
+    pub fn to_user_with<__PartialFn>(
+        self,
+        __partial_omitted: __PartialFn,
+        nickname: Option<String>,
+    ) -> User<'a, T, N>
+    where
+        __PartialFn: FnOnce() -> (u64, Vec<u8>),
+        T: Default,
+    {
+        let (id, secret) = __partial_omitted();
+        self.to_user(id, secret, nickname)
+    }
+    ///Applies this partial struct on top of `current`: included fields are always taken from this struct, optional fields only when `Some`, and omitted fields are kept from `current`.
+    #[inline]
+    pub fn to_user_or_current(self, current: User<'a, T, N>) -> User<'a, T, N>
+    where
+        T: Default,
+    {
+        User {
+            id: current.id,
+            r#type: self.r#type,
+            nickname: self.nickname.unwrap_or(current.nickname),
+            score: self.score.unwrap_or(current.score),
+            tags: self.tags,
+            secret: current.secret,
+        }
+    }
+    ///Rebuilds the full struct, taking the omitted fields (and any `None` optional fields) from `base`. Same as [`Self::to_user_or_current`].
+    #[inline]
+    pub fn to_user_from_base(self, base: User<'a, T, N>) -> User<'a, T, N>
+    where
+        T: Default,
+    {
+        self.to_user_or_current(base)
+    }
+    ///Writes this partial struct into `target`: included fields are always assigned, optional fields only when `Some`. Omitted fields are left untouched.
+    #[inline]
+    pub fn merge_into(self, target: &mut User<'a, T, N>)
+    where
+        T: Default,
+    {
+        target.r#type = self.r#type;
+        target.tags = self.tags;
+        if let Some(value) = self.nickname {
+            target.nickname = value;
+        }
+        if let Some(value) = self.score {
+            target.score = value;
+        }
+    }
+    ///Returns the names of the fields whose value differs from `full`, in declaration order. Optional fields are only compared when `Some`; omitted fields are ignored.
+    pub fn diff(&self, full: &User<'a, T, N>) -> ::std::vec::Vec<&'static str>
+    where
+        T: Default,
+        for<'__partial> &'a str: ::core::cmp::PartialEq,
+        for<'__partial> [T; N]: ::core::cmp::PartialEq,
+        for<'__partial> String: ::core::cmp::PartialEq,
+        for<'__partial> u32: ::core::cmp::PartialEq,
+    {
+        let mut changed = ::std::vec::Vec::new();
+        if self.r#type != full.r#type {
+            changed.push("type");
+        }
+        if let Some(value) = self.nickname.as_ref() {
+            if *value != full.nickname {
+                changed.push("nickname");
+            }
+        }
+        if let Some(value) = self.score.as_ref() {
+            if *value != full.score {
+                changed.push("score");
+            }
+        }
+        if self.tags != full.tags {
+            changed.push("tags");
+        }
+        changed
+    }
+    ///Splits the full struct into this partial struct and a struct containing the omitted fields.
+    #[inline]
+    pub fn from_user_with_omitted(full: User<'a, T, N>) -> (Self, DraftOmitted)
+    where
+        T: Default,
+    {
+        let User { id, r#type, nickname, score, tags, secret } = full;
+        (
+            Self {
+                r#type: r#type,
+                tags: tags,
+                nickname: Some(nickname),
+                score: Some(score),
+            },
+            DraftOmitted { id, secret },
+        )
+    }
+    ///Converts this partial struct into the full struct, taking the omitted fields from the struct returned by the split methods. Optional fields fall back to the given values when `None`.
+    #[inline]
+    pub fn take_omitted(
+        self,
+        __partial_omitted: DraftOmitted,
+        nickname: Option<String>,
+    ) -> User<'a, T, N>
+    where
+        T: Default,
+    {
+        let DraftOmitted { id, secret } = __partial_omitted;
+        self.to_user(id, secret, nickname)
+    }
+    ///Returns the omitted fields filled from their `default(...)` entries, or `Default` for the others, ready for `take_omitted`.
+    #[inline]
+    pub fn omitted_defaults() -> DraftOmitted
+    where
+        T: Default,
+        for<'__partial> u64: ::core::default::Default,
+        for<'__partial> Vec<u8>: ::core::default::Default,
+    {
+        let id = ::core::default::Default::default();
+        let secret = ::core::default::Default::default();
+        DraftOmitted { id, secret }
+    }
+    ///Folds `f` over the names of the optional fields that are `Some`, in declaration order.
+    #[inline]
+    pub fn fold_set_optionals<__PartialAcc>(
+        &self,
+        init: __PartialAcc,
+        f: impl FnMut(__PartialAcc, &'static str) -> __PartialAcc,
+    ) -> __PartialAcc {
+        let mut f = f;
+        let mut acc = init;
+        if self.nickname.as_ref().is_some() {
+            acc = f(acc, "nickname");
+        }
+        if self.score.as_ref().is_some() {
+            acc = f(acc, "score");
+        }
+        acc
+    }
+    ///Returns the names of the optional fields that are `Some`, in declaration order.
+    pub fn present_optionals(&self) -> ::std::vec::Vec<&'static str> {
+        self.fold_set_optionals(
+            ::std::vec::Vec::new(),
+            |mut present, name| {
+                present.push(name);
+                present
+            },
+        )
+    }
+    ///Returns the names of the optional fields that are `None`, in declaration order.
+    pub fn absent_optionals(&self) -> ::std::vec::Vec<&'static str> {
+        let mut absent = ::std::vec::Vec::new();
+        if self.nickname.as_ref().is_none() {
+            absent.push("nickname");
+        }
+        if self.score.as_ref().is_none() {
+            absent.push("score");
+        }
+        absent
+    }
+    ///Returns the name and `Display`-formatted value of every optional field that is `Some`, in declaration order (for example to build an SQL `UPDATE ... SET` clause).
+    pub fn changed(&self) -> ::std::vec::Vec<(&'static str, ::std::string::String)>
+    where
+        for<'__partial> String: ::core::fmt::Display,
+        for<'__partial> u32: ::core::fmt::Display,
+    {
+        let mut changed = ::std::vec::Vec::new();
+        if let Some(value) = self.nickname.as_ref() {
+            changed.push(("nickname", ::std::string::ToString::to_string(value)));
+        }
+        if let Some(value) = self.score.as_ref() {
+            changed.push(("score", ::std::string::ToString::to_string(value)));
+        }
+        changed
+    }
+    ///Calls `f` with the name and value of every field of this partial struct, in declaration order, for logging any partial generically.
+    pub fn for_each_field<__PartialFn: FnMut(&'static str, &dyn ::core::fmt::Debug)>(
+        &self,
+        mut f: __PartialFn,
+    )
+    where
+        for<'__partial> &'a str: ::core::fmt::Debug,
+        for<'__partial> Option<String>: ::core::fmt::Debug,
+        for<'__partial> Option<u32>: ::core::fmt::Debug,
+        for<'__partial> [T; N]: ::core::fmt::Debug,
+    {
+        f("type", &self.r#type);
+        f("nickname", &self.nickname);
+        f("score", &self.score);
+        f("tags", &self.tags);
+    }
+    ///Builds the partial struct from its fields in declaration order, each of the type it is stored as (optional fields as their `Option` or wrapper).
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_parts(
+        r#type: &'a str,
+        nickname: Option<String>,
+        score: Option<u32>,
+        tags: [T; N],
+    ) -> Self {
+        Self {
+            r#type,
+            nickname,
+            score,
+            tags,
+        }
+    }
+    ///Passes this partial struct through `f` when `cond` is true, and returns it unchanged otherwise.
+    #[inline]
+    pub fn apply_if(self, cond: bool, f: impl FnOnce(Self) -> Self) -> Self {
+        if cond { f(self) } else { self }
+    }
+    ///The name of the struct this partial struct was derived from.
+    pub const SOURCE_STRUCT: &'static str = "User";
+    ///Returns the names of the fields kept in this partial struct, in declaration order.
+    #[inline]
+    pub const fn included_field_names() -> &'static [&'static str] {
+        &["type", "nickname", "score", "tags"]
+    }
+    ///Returns the names of the fields omitted from this partial struct, in declaration order.
+    #[inline]
+    pub const fn omitted_field_names() -> &'static [&'static str] {
+        &["id", "secret"]
+    }
+    ///Describes the fields of this partial struct, in declaration order. Optional fields report the type they wrap.
+    pub fn schema() -> Vec<::partial_struct::FieldSchema> {
+        ::std::vec![
+            ::partial_struct::FieldSchema { name : "type", type_name :
+            ::core::stringify!(& 'a str), optional : false, },
+            ::partial_struct::FieldSchema { name : "nickname", type_name :
+            ::core::stringify!(String), optional : true, }, ::partial_struct::FieldSchema
+            { name : "score", type_name : ::core::stringify!(u32), optional : true, },
+            ::partial_struct::FieldSchema { name : "tags", type_name :
+            ::core::stringify!([T; N]), optional : false, }
+        ]
+    }
+}
+///Converts the full struct into this partial struct by projecting the included fields.
+impl<'a, T: Clone, const N: usize> From<User<'a, T, N>> for Draft<'a, T, N>
+where
+    T: Default,
+{
+    #[inline]
+    fn from(full: User<'a, T, N>) -> Self {
+        Self {
+            r#type: full.r#type,
+            tags: full.tags,
+            nickname: Some(full.nickname),
+            score: Some(full.score),
+        }
+    }
+}
+impl<'a, T: Clone, const N: usize> Draft<'a, T, N>
+where
+    T: Default,
+{
+    ///Borrows each field of this partial struct.
+    #[inline]
+    pub fn as_partial_ref<'__partial>(
+        &'__partial self,
+    ) -> DraftRef<'__partial, 'a, T, N> {
+        DraftRef {
+            r#type: &self.r#type,
+            tags: &self.tags,
+            nickname: self.nickname.as_ref(),
+            score: self.score.as_ref(),
+        }
+    }
+}
+///Borrows the included fields of the full struct.
+impl<'__partial, 'a, T: Clone, const N: usize> From<&'__partial User<'a, T, N>>
+for DraftRef<'__partial, 'a, T, N>
+where
+    T: Default,
+{
+    #[inline]
+    fn from(full: &'__partial User<'a, T, N>) -> Self {
+        Self {
+            r#type: &full.r#type,
+            tags: &full.tags,
+            nickname: Some(&full.nickname),
+            score: Some(&full.score),
+        }
+    }
+}
+impl<'__partial, 'a, T: Clone, const N: usize> DraftRef<'__partial, 'a, T, N>
+where
+    T: Default,
+{
+    ///Clones each borrowed field into an owned partial struct.
+    #[inline]
+    pub fn into_owned(self) -> Draft<'a, T, N>
+    where
+        &'a str: Clone,
+        [T; N]: Clone,
+        String: Clone,
+        u32: Clone,
+    {
+        Draft {
+            r#type: self.r#type.clone(),
+            tags: self.tags.clone(),
+            nickname: self.nickname.cloned(),
+            score: self.score.cloned(),
+        }
+    }
+}
+impl DraftOmitted {
+    ///Rebuilds the full struct from these omitted fields and the given partial. Same as [`Draft::take_omitted`].
+    #[must_use]
+    #[inline]
+    pub fn into_user<'a, T: Clone, const N: usize>(
+        self,
+        __partial_partial: Draft<'a, T, N>,
+        nickname: Option<String>,
+    ) -> User<'a, T, N>
+    where
+        T: Default,
+    {
+        __partial_partial.take_omitted(self, nickname)
+    }
+}
+///Compares the included fields with the full struct, ignoring omitted fields. Optional fields only match when they hold the full struct's value.
+impl<'a, T: Clone, const N: usize> PartialEq<User<'a, T, N>> for Draft<'a, T, N>
+where
+    T: Default,
+    &'a str: PartialEq,
+    [T; N]: PartialEq,
+    String: PartialEq,
+    u32: PartialEq,
+{
+    #[inline]
+    fn eq(&self, full: &User<'a, T, N>) -> bool {
+        let partial = self;
+        partial.r#type == full.r#type && partial.tags == full.tags
+            && partial.nickname.as_ref() == Some(&full.nickname)
+            && partial.score.as_ref() == Some(&full.score)
+    }
+}
+///Compares the included fields with the full struct, ignoring omitted fields. Optional fields only match when they hold the full struct's value.
+impl<'a, T: Clone, const N: usize> PartialEq<Draft<'a, T, N>> for User<'a, T, N>
+where
+    Draft<'a, T, N>: PartialEq<User<'a, T, N>>,
+    T: Default,
+{
+    #[inline]
+    fn eq(&self, partial: &Draft<'a, T, N>) -> bool {
+        partial == self
+    }
+}
+impl<'a, T: Clone, const N: usize> User<'a, T, N>
+where
+    T: Default,
+    &'a str: ::core::hash::Hash,
+    [T; N]: ::core::hash::Hash,
+    String: ::core::hash::Hash,
+    u32: ::core::hash::Hash,
+{
+    ///Hashes only the fields kept in `Draft`, matching the hash of the corresponding partial struct.
+    pub fn partial_hash(&self) -> u64 {
+        let mut hasher = ::std::collections::hash_map::DefaultHasher::new();
+        ::core::hash::Hash::hash(&self.r#type, &mut hasher);
+        ::core::hash::Hash::hash(&self.tags, &mut hasher);
+        ::core::hash::Hash::hash(&Some(&self.nickname), &mut hasher);
+        ::core::hash::Hash::hash(&Some(&self.score), &mut hasher);
+        ::core::hash::Hasher::finish(&hasher)
+    }
+}
+impl<'a, T: Clone, const N: usize> Draft<'a, T, N>
+where
+    T: Default,
+{
+    ///Layers `other` on top of this partial struct: `other`'s included fields and `Some` optional fields win, and `None` keeps this struct's value.
+    #[inline]
+    pub fn overlay(self, other: Self) -> Self {
+        Self {
+            r#type: other.r#type,
+            tags: other.tags,
+            nickname: other.nickname.or(self.nickname),
+            score: other.score.or(self.score),
+        }
+    }
+}
+///Overlays each partial struct in order, so the last `Some` value of every optional field wins.
+impl<'a, T: Clone, const N: usize> ::core::iter::Extend<Draft<'a, T, N>>
+for Draft<'a, T, N>
+where
+    T: Default,
+{
+    fn extend<__PartialIter: ::core::iter::IntoIterator<Item = Self>>(
+        &mut self,
+        patches: __PartialIter,
+    ) {
+        for patch in patches {
+            self.r#type = patch.r#type;
+            self.tags = patch.tags;
+            if let Some(value) = patch.nickname {
+                self.nickname = Some(value);
+            }
+            if let Some(value) = patch.score {
+                self.score = Some(value);
+            }
+        }
+    }
+}
+impl<'a, T: Clone, const N: usize> User<'a, T, N>
+where
+    T: Default,
+{
+    ///Splits this struct into its partial representation and a struct containing the omitted fields.
+    #[inline]
+    pub fn into_draft_with_omitted(self) -> (Draft<'a, T, N>, DraftOmitted) {
+        Draft::from_user_with_omitted(self)
+    }
+    ///Copies the fields of a `Draft` onto this struct in place: included fields always, optional fields only when `Some`. Omitted fields are left untouched.
+    #[inline]
+    pub fn apply_draft(&mut self, partial: Draft<'a, T, N>) {
+        partial.merge_into(self)
+    }
+}
+///A partial version of `User` omitting the field(s): score, tags, secret. Field attributes are copied.
+///
+///Split a `User` with [`Summary::from_user_with_omitted`] and rebuild it with [`Summary::to_user`].
+#[derive(Debug, PartialEq, Eq)]
+pub struct Summary<'a> {
+    pub id: u64,
+    pub r#type: &'a str,
+    pub nickname: String,
+}
+///Fields omitted from `User` when projecting into `Summary`.
+pub struct SummaryOmitted<T: Clone, const N: usize>
+where
+    T: Default,
+{
+    pub score: u32,
+    pub tags: [T; N],
+    pub secret: Vec<u8>,
+}
+///The fields of `Summary` in declaration order, as a tuple struct.
+#[derive(Debug, PartialEq, Eq)]
+pub struct SummaryTuple<'a>(pub u64, pub &'a str, pub String);
+///Why a `Summary` could not be converted into `User`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SummaryCompleteError {
+    /// An optional field without a default was `None`.
+    MissingField(&'static str),
+    /// The rebuilt struct was rejected by `partial_struct::Validate`.
+    Invalid(::std::string::String),
+}
+///Orders by the sort key field(s): type, then by the remaining fields in declaration order.
+impl<'a> ::core::cmp::Ord for Summary<'a>
+where
+    &'a str: ::core::cmp::Ord,
+    u64: ::core::cmp::Ord,
+    String: ::core::cmp::Ord,
+{
+    fn cmp(&self, other: &Self) -> ::core::cmp::Ordering {
+        ::core::cmp::Ordering::Equal
+            .then_with(|| ::core::cmp::Ord::cmp(&self.r#type, &other.r#type))
+            .then_with(|| ::core::cmp::Ord::cmp(&self.id, &other.id))
+            .then_with(|| ::core::cmp::Ord::cmp(&self.nickname, &other.nickname))
+    }
+}
+///Orders by the sort key field(s): type, then by the remaining fields in declaration order.
+impl<'a> ::core::cmp::PartialOrd for Summary<'a>
+where
+    &'a str: ::core::cmp::Ord,
+    u64: ::core::cmp::Ord,
+    String: ::core::cmp::Ord,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<::core::cmp::Ordering> {
+        Some(::core::cmp::Ord::cmp(self, other))
+    }
+}
+impl<'a> Summary<'a> {
+    ///Converts this partial struct into the full struct by providing the omitted fields.
+    #[must_use]
+    #[inline]
+    pub fn to_user<T: Clone, const N: usize>(
+        self,
+        score: u32,
+        tags: [T; N],
+        secret: Vec<u8>,
+    ) -> User<'a, T, N>
+    where
+        T: Default,
+    {
+        User {
+            id: self.id,
+            r#type: self.r#type,
+            nickname: self.nickname,
+            score: score,
+            tags: tags,
+            secret: secret,
+        }
+    }
+    ///Creates a new full struct by cloning the fields from this partial struct and providing the omitted fields.
+    ///Requires that all included fields implement `Clone`.
+    #[must_use]
+    #[inline]
+    pub fn to_user_cloned<T: Clone, const N: usize>(
+        &self,
+        score: u32,
+        tags: [T; N],
+        secret: Vec<u8>,
+    ) -> User<'a, T, N>
+    where
+        T: Default,
+        u64: Clone,
+        &'a str: Clone,
+        String: Clone,
+    {
+        User {
+            id: self.id.clone(),
+            r#type: self.r#type.clone(),
+            nickname: self.nickname.clone(),
+            score: score,
+            tags: tags,
+            secret: secret,
+        }
+    }
+    ///Converts this partial struct into the full struct, calling the given closure for the omitted fields as a tuple in declaration order. The closure only runs here, so costly values are computed only when the full struct is rebuilt.
+    #[inline]
+    pub fn to_user_with<T: Clone, const N: usize, __PartialFn>(
+        self,
+        __partial_omitted: __PartialFn,
+    ) -> User<'a, T, N>
+    where
+        __PartialFn: FnOnce() -> (u32, [T; N], Vec<u8>),
+        T: Default,
+    {
+        let (score, tags, secret) = __partial_omitted();
+        self.to_user(score, tags, secret)
+    }
+    ///Applies this partial struct on top of `current`: included fields are always taken from this struct, optional fields only when `Some`, and omitted fields are kept from `current`.
+    #[inline]
+    pub fn to_user_or_current<T: Clone, const N: usize>(
+        self,
+        current: User<'a, T, N>,
+    ) -> User<'a, T, N>
+    where
+        T: Default,
+    {
+        User {
+            id: self.id,
+            r#type: self.r#type,
+            nickname: self.nickname,
+            score: current.score,
+            tags: current.tags,
+            secret: current.secret,
+        }
+    }
+    ///Rebuilds the full struct, taking the omitted fields (and any `None` optional fields) from `base`. Same as [`Self::to_user_or_current`].
+    #[inline]
+    pub fn to_user_from_base<T: Clone, const N: usize>(
+        self,
+        base: User<'a, T, N>,
+    ) -> User<'a, T, N>
+    where
+        T: Default,
+    {
+        self.to_user_or_current(base)
+    }
+    ///Writes this partial struct into `target`: included fields are always assigned, optional fields only when `Some`. Omitted fields are left untouched.
+    #[inline]
+    pub fn merge_into<T: Clone, const N: usize>(self, target: &mut User<'a, T, N>)
+    where
+        T: Default,
+    {
+        target.id = self.id;
+        target.r#type = self.r#type;
+        target.nickname = self.nickname;
+    }
+    ///Returns the names of the fields whose value differs from `full`, in declaration order. Optional fields are only compared when `Some`; omitted fields are ignored.
+    pub fn diff<T: Clone, const N: usize>(
+        &self,
+        full: &User<'a, T, N>,
+    ) -> ::std::vec::Vec<&'static str>
+    where
+        T: Default,
+        for<'__partial> u64: ::core::cmp::PartialEq,
+        for<'__partial> &'a str: ::core::cmp::PartialEq,
+        for<'__partial> String: ::core::cmp::PartialEq,
+    {
+        let mut changed = ::std::vec::Vec::new();
+        if self.id != full.id {
+            changed.push("id");
+        }
+        if self.r#type != full.r#type {
+            changed.push("type");
+        }
+        if self.nickname != full.nickname {
+            changed.push("nickname");
+        }
+        changed
+    }
+    ///Splits the full struct into this partial struct and a struct containing the omitted fields.
+    #[inline]
+    pub fn from_user_with_omitted<T: Clone, const N: usize>(
+        full: User<'a, T, N>,
+    ) -> (Self, SummaryOmitted<T, N>)
+    where
+        T: Default,
+    {
+        let User { id, r#type, nickname, score, tags, secret } = full;
+        (
+            Self {
+                id: id,
+                r#type: r#type,
+                nickname: nickname,
+            },
+            SummaryOmitted {
+                score,
+                tags,
+                secret,
+            },
+        )
+    }
+    ///Converts this partial struct into the full struct, taking the omitted fields from the struct returned by the split methods. Optional fields fall back to the given values when `None`.
+    #[inline]
+    pub fn take_omitted<T: Clone, const N: usize>(
+        self,
+        __partial_omitted: SummaryOmitted<T, N>,
+    ) -> User<'a, T, N>
+    where
+        T: Default,
+    {
+        let SummaryOmitted { score, tags, secret } = __partial_omitted;
+        self.to_user(score, tags, secret)
+    }
+    ///Returns the omitted fields filled from their `default(...)` entries, or `Default` for the others, ready for `take_omitted`.
+    #[inline]
+    pub fn omitted_defaults<T: Clone, const N: usize>() -> SummaryOmitted<T, N>
+    where
+        T: Default,
+        for<'__partial> u32: ::core::default::Default,
+        for<'__partial> [T; N]: ::core::default::Default,
+        for<'__partial> Vec<u8>: ::core::default::Default,
+    {
+        let score = ::core::default::Default::default();
+        let tags = ::core::default::Default::default();
+        let secret = ::core::default::Default::default();
+        SummaryOmitted {
+            score,
+            tags,
+            secret,
+        }
+    }
+    ///Folds `f` over the names of the optional fields that are `Some`, in declaration order.
+    #[inline]
+    pub fn fold_set_optionals<__PartialAcc>(
+        &self,
+        init: __PartialAcc,
+        f: impl FnMut(__PartialAcc, &'static str) -> __PartialAcc,
+    ) -> __PartialAcc {
+        let _ = f;
+        init
+    }
+    ///Returns the names of the optional fields that are `Some`, in declaration order.
+    pub fn present_optionals(&self) -> ::std::vec::Vec<&'static str> {
+        self.fold_set_optionals(
+            ::std::vec::Vec::new(),
+            |mut present, name| {
+                present.push(name);
+                present
+            },
+        )
+    }
+    ///Returns the names of the optional fields that are `None`, in declaration order.
+    pub fn absent_optionals(&self) -> ::std::vec::Vec<&'static str> {
+        ::std::vec::Vec::new()
+    }
+    ///Returns the name and `Display`-formatted value of every optional field that is `Some`, in declaration order (for example to build an SQL `UPDATE ... SET` clause).
+    pub fn changed(&self) -> ::std::vec::Vec<(&'static str, ::std::string::String)> {
+        ::std::vec::Vec::new()
+    }
+    ///Calls `f` with the name and value of every field of this partial struct, in declaration order, for logging any partial generically.
+    pub fn for_each_field<__PartialFn: FnMut(&'static str, &dyn ::core::fmt::Debug)>(
+        &self,
+        mut f: __PartialFn,
+    )
+    where
+        for<'__partial> u64: ::core::fmt::Debug,
+        for<'__partial> &'a str: ::core::fmt::Debug,
+        for<'__partial> String: ::core::fmt::Debug,
+    {
+        f("id", &self.id);
+        f("type", &self.r#type);
+        f("nickname", &self.nickname);
+    }
+    ///Builds the partial struct from its fields in declaration order, each of the type it is stored as (optional fields as their `Option` or wrapper).
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_parts(id: u64, r#type: &'a str, nickname: String) -> Self {
+        Self { id, r#type, nickname }
+    }
+    ///Passes this partial struct through `f` when `cond` is true, and returns it unchanged otherwise.
+    #[inline]
+    pub fn apply_if(self, cond: bool, f: impl FnOnce(Self) -> Self) -> Self {
+        if cond { f(self) } else { self }
+    }
+    ///The name of the struct this partial struct was derived from.
+    pub const SOURCE_STRUCT: &'static str = "User";
+    ///Returns the names of the fields kept in this partial struct, in declaration order.
+    #[inline]
+    pub const fn included_field_names() -> &'static [&'static str] {
+        &["id", "type", "nickname"]
+    }
+    ///Returns the names of the fields omitted from this partial struct, in declaration order.
+    #[inline]
+    pub const fn omitted_field_names() -> &'static [&'static str] {
+        &["score", "tags", "secret"]
+    }
+    ///Describes the fields of this partial struct, in declaration order. Optional fields report the type they wrap.
+    pub fn schema() -> Vec<::partial_struct::FieldSchema> {
+        ::std::vec![
+            ::partial_struct::FieldSchema { name : "id", type_name :
+            ::core::stringify!(u64), optional : false, }, ::partial_struct::FieldSchema {
+            name : "type", type_name : ::core::stringify!(& 'a str), optional : false, },
+            ::partial_struct::FieldSchema { name : "nickname", type_name :
+            ::core::stringify!(String), optional : false, }
+        ]
+    }
+}
+///Converts the full struct into this partial struct by projecting the included fields.
+impl<'a, T: Clone, const N: usize> From<User<'a, T, N>> for Summary<'a>
+where
+    T: Default,
+{
+    #[inline]
+    fn from(full: User<'a, T, N>) -> Self {
+        Self {
+            id: full.id,
+            r#type: full.r#type,
+            nickname: full.nickname,
+        }
+    }
+}
+///Converts a shared full struct into this partial struct by cloning the included fields.
+impl<'a, T: Clone, const N: usize> From<&::std::sync::Arc<User<'a, T, N>>>
+for Summary<'a>
+where
+    T: Default,
+    u64: Clone,
+    &'a str: Clone,
+    String: Clone,
+{
+    #[inline]
+    fn from(full: &::std::sync::Arc<User<'a, T, N>>) -> Self {
+        Self {
+            id: full.id.clone(),
+            r#type: full.r#type.clone(),
+            nickname: full.nickname.clone(),
+        }
+    }
+}
+///Converts a shared full struct into this partial struct by cloning the included fields.
+impl<'a, T: Clone, const N: usize> From<&::std::rc::Rc<User<'a, T, N>>> for Summary<'a>
+where
+    T: Default,
+    u64: Clone,
+    &'a str: Clone,
+    String: Clone,
+{
+    #[inline]
+    fn from(full: &::std::rc::Rc<User<'a, T, N>>) -> Self {
+        Self {
+            id: full.id.clone(),
+            r#type: full.r#type.clone(),
+            nickname: full.nickname.clone(),
+        }
+    }
+}
+///Converts this partial struct into its positional tuple-struct form.
+impl<'a> From<Summary<'a>> for SummaryTuple<'a> {
+    #[inline]
+    fn from(partial: Summary<'a>) -> Self {
+        Self(partial.id, partial.r#type, partial.nickname)
+    }
+}
+impl<'a> Summary<'a> {
+    ///Converts this partial struct into its positional tuple-struct form.
+    #[inline]
+    pub fn into_tuple_struct(self) -> SummaryTuple<'a> {
+        self.into()
+    }
+}
+impl ::core::fmt::Display for SummaryCompleteError {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        match self {
+            Self::MissingField(name) => {
+                write!(f, "missing field `{}` for `{}`", name, "User")
+            }
+            Self::Invalid(message) => write!(f, "invalid `{}`: {}", "User", message),
+        }
+    }
+}
+impl ::std::error::Error for SummaryCompleteError {}
+///Rebuilds the full struct: optional fields must be `Some` (or have a default), omitted fields are filled with `Default::default()`, and the result must pass `partial_struct::Validate`.
+impl<'a, T: Clone, const N: usize> ::core::convert::TryFrom<Summary<'a>>
+for User<'a, T, N>
+where
+    T: Default,
+    u32: ::core::default::Default,
+    [T; N]: ::core::default::Default,
+    Vec<u8>: ::core::default::Default,
+    User<'a, T, N>: ::partial_struct::Validate,
+{
+    type Error = SummaryCompleteError;
+    fn try_from(partial: Summary<'a>) -> ::core::result::Result<Self, Self::Error> {
+        let full = User {
+            id: partial.id,
+            r#type: partial.r#type,
+            nickname: partial.nickname,
+            score: ::core::default::Default::default(),
+            tags: ::core::default::Default::default(),
+            secret: ::core::default::Default::default(),
+        };
+        ::partial_struct::Validate::validate(&full)
+            .map_err(SummaryCompleteError::Invalid)?;
+        ::core::result::Result::Ok(full)
+    }
+}
+impl<T: Clone, const N: usize> SummaryOmitted<T, N>
+where
+    T: Default,
+{
+    ///Rebuilds the full struct from these omitted fields and the given partial. Same as [`Summary::take_omitted`].
+    #[must_use]
+    #[inline]
+    pub fn into_user<'a>(self, __partial_partial: Summary<'a>) -> User<'a, T, N>
+    where
+        T: Default,
+    {
+        __partial_partial.take_omitted(self)
+    }
+}
+impl<'a, T: Clone, const N: usize> User<'a, T, N>
+where
+    T: Default,
+{
+    ///Splits this struct into its partial representation and a struct containing the omitted fields.
+    #[inline]
+    pub fn into_summary_with_omitted(self) -> (Summary<'a>, SummaryOmitted<T, N>) {
+        Summary::from_user_with_omitted(self)
+    }
+    ///Copies the fields of a `Summary` onto this struct in place: included fields always, optional fields only when `Some`. Omitted fields are left untouched.
+    #[inline]
+    pub fn apply_summary(&mut self, partial: Summary<'a>) {
+        partial.merge_into(self)
+    }
+}
+///A partial version of `User` omitting the field(s): id; fields made optional: type, nickname, score, tags, secret. Field attributes are copied.
+///
+///Split a `User` with [`Patch::from_user_with_omitted`] and rebuild it with [`Patch::apply_to`].
+pub struct Patch<'a, T: Clone, const N: usize>
+where
+    T: Default,
+{
+    pub r#type: Option<&'a str>,
+    pub nickname: Option<String>,
+    pub score: Option<u32>,
+    pub tags: Option<[T; N]>,
+    pub secret: Option<Vec<u8>>,
+}
+///Fields omitted from `User` when projecting into `Patch`.
+pub struct PatchOmitted {
+    pub id: u64,
+}
+const _: () = {
+    impl<'a, T: Clone, const N: usize> Patch<'a, T, N>
+    where
+        T: Default,
+    {
+        ///Converts this partial struct into the full struct by providing the omitted fields.
+        #[must_use]
+        #[inline]
+        pub fn apply_to(
+            self,
+            id: u64,
+            r#type: Option<&'a str>,
+            nickname: Option<String>,
+            score: Option<u32>,
+            tags: Option<[T; N]>,
+            secret: Option<Vec<u8>>,
+        ) -> User<'a, T, N>
+        where
+            T: Default,
+        {
+            User {
+                id: id,
+                r#type: self.r#type.or(r#type).expect("Optional field must be provided"),
+                nickname: self
+                    .nickname
+                    .or(nickname)
+                    .expect("Optional field must be provided"),
+                score: self.score.or(score).expect("Optional field must be provided"),
+                tags: self.tags.or(tags).expect("Optional field must be provided"),
+                secret: self.secret.or(secret).expect("Optional field must be provided"),
+            }
+        }
+        ///Creates a new full struct by cloning the fields from this partial struct and providing the omitted fields.
+        ///Requires that all included fields implement `Clone`.
+        #[must_use]
+        #[inline]
+        pub fn apply_to_cloned(
+            &self,
+            id: u64,
+            r#type: Option<&'a str>,
+            nickname: Option<String>,
+            score: Option<u32>,
+            tags: Option<[T; N]>,
+            secret: Option<Vec<u8>>,
+        ) -> User<'a, T, N>
+        where
+            T: Default,
+            Option<&'a str>: Clone,
+            Option<String>: Clone,
+            Option<u32>: Clone,
+            Option<[T; N]>: Clone,
+            Option<Vec<u8>>: Clone,
+        {
+            User {
+                id: id,
+                r#type: self
+                    .r#type
+                    .clone()
+                    .or(r#type)
+                    .expect("Optional field must be provided"),
+                nickname: self
+                    .nickname
+                    .clone()
+                    .or(nickname)
+                    .expect("Optional field must be provided"),
+                score: self
+                    .score
+                    .clone()
+                    .or(score)
+                    .expect("Optional field must be provided"),
+                tags: self
+                    .tags
+                    .clone()
+                    .or(tags)
+                    .expect("Optional field must be provided"),
+                secret: self
+                    .secret
+                    .clone()
+                    .or(secret)
+                    .expect("Optional field must be provided"),
+            }
+        }
+        ///Converts this partial struct into the full struct, calling the given closure for the omitted fields as a tuple in declaration order. The closure only runs here, so costly values are computed only when the full struct is rebuilt.
+        #[inline]
+        pub fn apply_to_with<__PartialFn>(
+            self,
+            __partial_omitted: __PartialFn,
+            r#type: Option<&'a str>,
+            nickname: Option<String>,
+            score: Option<u32>,
+            tags: Option<[T; N]>,
+            secret: Option<Vec<u8>>,
+        ) -> User<'a, T, N>
+        where
+            __PartialFn: FnOnce() -> (u64,),
+            T: Default,
+        {
+            let (id,) = __partial_omitted();
+            self.apply_to(id, r#type, nickname, score, tags, secret)
+        }
+        ///Applies this partial struct on top of `current`: included fields are always taken from this struct, optional fields only when `Some`, and omitted fields are kept from `current`.
+        #[inline]
+        pub fn apply_to_or_current(self, current: User<'a, T, N>) -> User<'a, T, N>
+        where
+            T: Default,
+        {
+            User {
+                id: current.id,
+                r#type: self.r#type.unwrap_or(current.r#type),
+                nickname: self.nickname.unwrap_or(current.nickname),
+                score: self.score.unwrap_or(current.score),
+                tags: self.tags.unwrap_or(current.tags),
+                secret: self.secret.unwrap_or(current.secret),
+            }
+        }
+        ///Rebuilds the full struct, taking the omitted fields (and any `None` optional fields) from `base`. Same as [`Self::apply_to_or_current`].
+        #[inline]
+        pub fn apply_to_from_base(self, base: User<'a, T, N>) -> User<'a, T, N>
+        where
+            T: Default,
+        {
+            self.apply_to_or_current(base)
+        }
+        ///Writes this partial struct into `target`: included fields are always assigned, optional fields only when `Some`. Omitted fields are left untouched.
+        #[inline]
+        pub fn merge_into(self, target: &mut User<'a, T, N>)
+        where
+            T: Default,
+        {
+            if let Some(value) = self.r#type {
+                target.r#type = value;
+            }
+            if let Some(value) = self.nickname {
+                target.nickname = value;
+            }
+            if let Some(value) = self.score {
+                target.score = value;
+            }
+            if let Some(value) = self.tags {
+                target.tags = value;
+            }
+            if let Some(value) = self.secret {
+                target.secret = value;
+            }
+        }
+        ///Returns the names of the fields whose value differs from `full`, in declaration order. Optional fields are only compared when `Some`; omitted fields are ignored.
+        pub fn diff(&self, full: &User<'a, T, N>) -> ::std::vec::Vec<&'static str>
+        where
+            T: Default,
+            for<'__partial> &'a str: ::core::cmp::PartialEq,
+            for<'__partial> String: ::core::cmp::PartialEq,
+            for<'__partial> u32: ::core::cmp::PartialEq,
+            for<'__partial> [T; N]: ::core::cmp::PartialEq,
+            for<'__partial> Vec<u8>: ::core::cmp::PartialEq,
+        {
+            let mut changed = ::std::vec::Vec::new();
+            if let Some(value) = self.r#type.as_ref() {
+                if *value != full.r#type {
+                    changed.push("type");
+                }
+            }
+            if let Some(value) = self.nickname.as_ref() {
+                if *value != full.nickname {
+                    changed.push("nickname");
+                }
+            }
+            if let Some(value) = self.score.as_ref() {
+                if *value != full.score {
+                    changed.push("score");
+                }
+            }
+            if let Some(value) = self.tags.as_ref() {
+                if *value != full.tags {
+                    changed.push("tags");
+                }
+            }
+            if let Some(value) = self.secret.as_ref() {
+                if *value != full.secret {
+                    changed.push("secret");
+                }
+            }
+            changed
+        }
+        ///Splits the full struct into this partial struct and a struct containing the omitted fields.
+        #[inline]
+        pub fn from_user_with_omitted(full: User<'a, T, N>) -> (Self, PatchOmitted)
+        where
+            T: Default,
+        {
+            let User { id, r#type, nickname, score, tags, secret } = full;
+            (
+                Self {
+                    r#type: Some(r#type),
+                    nickname: Some(nickname),
+                    score: Some(score),
+                    tags: Some(tags),
+                    secret: Some(secret),
+                },
+                PatchOmitted { id },
+            )
+        }
+        ///Converts this partial struct into the full struct, taking the omitted fields from the struct returned by the split methods. Optional fields fall back to the given values when `None`.
+        #[inline]
+        pub fn take_omitted(
+            self,
+            __partial_omitted: PatchOmitted,
+            r#type: Option<&'a str>,
+            nickname: Option<String>,
+            score: Option<u32>,
+            tags: Option<[T; N]>,
+            secret: Option<Vec<u8>>,
+        ) -> User<'a, T, N>
+        where
+            T: Default,
+        {
+            let PatchOmitted { id } = __partial_omitted;
+            self.apply_to(id, r#type, nickname, score, tags, secret)
+        }
+        ///Returns the omitted fields filled from their `default(...)` entries, or `Default` for the others, ready for `take_omitted`.
+        #[inline]
+        pub fn omitted_defaults() -> PatchOmitted
+        where
+            T: Default,
+            for<'__partial> u64: ::core::default::Default,
+        {
+            let id = ::core::default::Default::default();
+            PatchOmitted { id }
+        }
+        ///Folds `f` over the names of the optional fields that are `Some`, in declaration order.
+        #[inline]
+        pub fn fold_set_optionals<__PartialAcc>(
+            &self,
+            init: __PartialAcc,
+            f: impl FnMut(__PartialAcc, &'static str) -> __PartialAcc,
+        ) -> __PartialAcc {
+            let mut f = f;
+            let mut acc = init;
+            if self.r#type.as_ref().is_some() {
+                acc = f(acc, "type");
+            }
+            if self.nickname.as_ref().is_some() {
+                acc = f(acc, "nickname");
+            }
+            if self.score.as_ref().is_some() {
+                acc = f(acc, "score");
+            }
+            if self.tags.as_ref().is_some() {
+                acc = f(acc, "tags");
+            }
+            if self.secret.as_ref().is_some() {
+                acc = f(acc, "secret");
+            }
+            acc
+        }
+        ///Returns the names of the optional fields that are `Some`, in declaration order.
+        pub fn present_optionals(&self) -> ::std::vec::Vec<&'static str> {
+            self.fold_set_optionals(
+                ::std::vec::Vec::new(),
+                |mut present, name| {
+                    present.push(name);
+                    present
+                },
+            )
+        }
+        ///Returns the names of the optional fields that are `None`, in declaration order.
+        pub fn absent_optionals(&self) -> ::std::vec::Vec<&'static str> {
+            let mut absent = ::std::vec::Vec::new();
+            if self.r#type.as_ref().is_none() {
+                absent.push("type");
+            }
+            if self.nickname.as_ref().is_none() {
+                absent.push("nickname");
+            }
+            if self.score.as_ref().is_none() {
+                absent.push("score");
+            }
+            if self.tags.as_ref().is_none() {
+                absent.push("tags");
+            }
+            if self.secret.as_ref().is_none() {
+                absent.push("secret");
+            }
+            absent
+        }
+        ///Returns the name and `Display`-formatted value of every optional field that is `Some`, in declaration order (for example to build an SQL `UPDATE ... SET` clause).
+        pub fn changed(&self) -> ::std::vec::Vec<(&'static str, ::std::string::String)>
+        where
+            for<'__partial> &'a str: ::core::fmt::Display,
+            for<'__partial> String: ::core::fmt::Display,
+            for<'__partial> u32: ::core::fmt::Display,
+            for<'__partial> [T; N]: ::core::fmt::Display,
+            for<'__partial> Vec<u8>: ::core::fmt::Display,
+        {
+            let mut changed = ::std::vec::Vec::new();
+            if let Some(value) = self.r#type.as_ref() {
+                changed.push(("type", ::std::string::ToString::to_string(value)));
+            }
+            if let Some(value) = self.nickname.as_ref() {
+                changed.push(("nickname", ::std::string::ToString::to_string(value)));
+            }
+            if let Some(value) = self.score.as_ref() {
+                changed.push(("score", ::std::string::ToString::to_string(value)));
+            }
+            if let Some(value) = self.tags.as_ref() {
+                changed.push(("tags", ::std::string::ToString::to_string(value)));
+            }
+            if let Some(value) = self.secret.as_ref() {
+                changed.push(("secret", ::std::string::ToString::to_string(value)));
+            }
+            changed
+        }
+        ///Calls `f` with the name and value of every field of this partial struct, in declaration order, for logging any partial generically.
+        pub fn for_each_field<__PartialFn: FnMut(&'static str, &dyn ::core::fmt::Debug)>(
+            &self,
+            mut f: __PartialFn,
+        )
+        where
+            for<'__partial> Option<&'a str>: ::core::fmt::Debug,
+            for<'__partial> Option<String>: ::core::fmt::Debug,
+            for<'__partial> Option<u32>: ::core::fmt::Debug,
+            for<'__partial> Option<[T; N]>: ::core::fmt::Debug,
+            for<'__partial> Option<Vec<u8>>: ::core::fmt::Debug,
+        {
+            f("type", &self.r#type);
+            f("nickname", &self.nickname);
+            f("score", &self.score);
+            f("tags", &self.tags);
+            f("secret", &self.secret);
+        }
+        ///Builds the partial struct from its fields in declaration order, each of the type it is stored as (optional fields as their `Option` or wrapper).
+        #[inline]
+        #[allow(clippy::too_many_arguments)]
+        pub fn from_parts(
+            r#type: Option<&'a str>,
+            nickname: Option<String>,
+            score: Option<u32>,
+            tags: Option<[T; N]>,
+            secret: Option<Vec<u8>>,
+        ) -> Self {
+            Self {
+                r#type,
+                nickname,
+                score,
+                tags,
+                secret,
+            }
+        }
+        ///Passes this partial struct through `f` when `cond` is true, and returns it unchanged otherwise.
+        #[inline]
+        pub fn apply_if(self, cond: bool, f: impl FnOnce(Self) -> Self) -> Self {
+            if cond { f(self) } else { self }
+        }
+        ///The name of the struct this partial struct was derived from.
+        pub const SOURCE_STRUCT: &'static str = "User";
+        ///Returns the names of the fields kept in this partial struct, in declaration order.
+        #[inline]
+        pub const fn included_field_names() -> &'static [&'static str] {
+            &["type", "nickname", "score", "tags", "secret"]
+        }
+        ///Returns the names of the fields omitted from this partial struct, in declaration order.
+        #[inline]
+        pub const fn omitted_field_names() -> &'static [&'static str] {
+            &["id"]
+        }
+        ///Describes the fields of this partial struct, in declaration order. Optional fields report the type they wrap.
+        pub fn schema() -> Vec<::partial_struct::FieldSchema> {
+            ::std::vec![
+                ::partial_struct::FieldSchema { name : "type", type_name :
+                ::core::stringify!(& 'a str), optional : true, },
+                ::partial_struct::FieldSchema { name : "nickname", type_name :
+                ::core::stringify!(String), optional : true, },
+                ::partial_struct::FieldSchema { name : "score", type_name :
+                ::core::stringify!(u32), optional : true, },
+                ::partial_struct::FieldSchema { name : "tags", type_name :
+                ::core::stringify!([T; N]), optional : true, },
+                ::partial_struct::FieldSchema { name : "secret", type_name :
+                ::core::stringify!(Vec < u8 >), optional : true, }
+            ]
+        }
+    }
+    ///Converts the full struct into this partial struct by projecting the included fields.
+    impl<'a, T: Clone, const N: usize> From<User<'a, T, N>> for Patch<'a, T, N>
+    where
+        T: Default,
+    {
+        #[inline]
+        fn from(full: User<'a, T, N>) -> Self {
+            Self {
+                r#type: Some(full.r#type),
+                nickname: Some(full.nickname),
+                score: Some(full.score),
+                tags: Some(full.tags),
+                secret: Some(full.secret),
+            }
+        }
+    }
+    impl<'a, T: Clone, const N: usize> Patch<'a, T, N>
+    where
+        T: Default,
+    {
+        ///Returns a partial struct with every field unset, to build a patch from.
+        #[inline]
+        pub const fn empty() -> Self {
+            Self {
+                r#type: None,
+                nickname: None,
+                score: None,
+                tags: None,
+                secret: None,
+            }
+        }
+    }
+    impl PatchOmitted {
+        ///Rebuilds the full struct from these omitted fields and the given partial. Same as [`Patch::take_omitted`].
+        #[must_use]
+        #[inline]
+        pub fn into_user<'a, T: Clone, const N: usize>(
+            self,
+            __partial_partial: Patch<'a, T, N>,
+            r#type: Option<&'a str>,
+            nickname: Option<String>,
+            score: Option<u32>,
+            tags: Option<[T; N]>,
+            secret: Option<Vec<u8>>,
+        ) -> User<'a, T, N>
+        where
+            T: Default,
+        {
+            __partial_partial.take_omitted(self, r#type, nickname, score, tags, secret)
+        }
+    }
+    impl<'a, T: Clone, const N: usize> Patch<'a, T, N>
+    where
+        T: Default,
+    {
+        ///Layers `other` on top of this partial struct: `other`'s included fields and `Some` optional fields win, and `None` keeps this struct's value.
+        #[inline]
+        pub fn overlay(self, other: Self) -> Self {
+            Self {
+                r#type: other.r#type.or(self.r#type),
+                nickname: other.nickname.or(self.nickname),
+                score: other.score.or(self.score),
+                tags: other.tags.or(self.tags),
+                secret: other.secret.or(self.secret),
+            }
+        }
+    }
+    ///Overlays each partial struct in order, so the last `Some` value of every optional field wins.
+    impl<'a, T: Clone, const N: usize> ::core::iter::Extend<Patch<'a, T, N>>
+    for Patch<'a, T, N>
+    where
+        T: Default,
+    {
+        fn extend<__PartialIter: ::core::iter::IntoIterator<Item = Self>>(
+            &mut self,
+            patches: __PartialIter,
+        ) {
+            for patch in patches {
+                if let Some(value) = patch.r#type {
+                    self.r#type = Some(value);
+                }
+                if let Some(value) = patch.nickname {
+                    self.nickname = Some(value);
+                }
+                if let Some(value) = patch.score {
+                    self.score = Some(value);
+                }
+                if let Some(value) = patch.tags {
+                    self.tags = Some(value);
+                }
+                if let Some(value) = patch.secret {
+                    self.secret = Some(value);
+                }
+            }
+        }
+    }
+    impl<'a, T: Clone, const N: usize> User<'a, T, N>
+    where
+        T: Default,
+    {
+        ///Splits this struct into its partial representation and a struct containing the omitted fields.
+        #[inline]
+        pub fn into_patch_with_omitted(self) -> (Patch<'a, T, N>, PatchOmitted) {
+            Patch::from_user_with_omitted(self)
+        }
+        ///Copies the fields of a `Patch` onto this struct in place: included fields always, optional fields only when `Some`. Omitted fields are left untouched.
+        #[inline]
+        pub fn apply_patch(&mut self, partial: Patch<'a, T, N>) {
+            partial.merge_into(self)
+        }
+    }
+};
+///A partial version of `User` omitting the field(s): secret. Field attributes are copied.
+///
+///Split a `User` with [`Projection::from_user_with_omitted`] and rebuild it with [`Projection::to_user`].
+pub struct Projection<'a, T: Clone, const N: usize>
+where
+    T: Default,
+{
+    pub id: u64,
+    pub r#type: &'a str,
+    pub nickname: String,
+    pub score: u32,
+    pub tags: [T; N],
+}
+///Fields omitted from `User` when projecting into `Projection`.
+pub struct ProjectionOmitted {
+    pub secret: Vec<u8>,
+}
+impl<'a, T: Clone, const N: usize> Projection<'a, T, N>
+where
+    T: Default,
+{
+    ///Converts this partial struct into the full struct by providing the omitted fields.
+    #[must_use]
+    #[inline]
+    pub fn to_user(self, secret: Vec<u8>) -> User<'a, T, N>
+    where
+        T: Default,
+    {
+        User {
+            id: self.id,
+            r#type: self.r#type,
+            nickname: self.nickname,
+            score: self.score,
+            tags: self.tags,
+            secret: secret,
+        }
+    }
+    ///Creates a new full struct by cloning the fields from this partial struct and providing the omitted fields.
+    ///Requires that all included fields implement `Clone`.
+    #[must_use]
+    #[inline]
+    pub fn to_user_cloned(&self, secret: Vec<u8>) -> User<'a, T, N>
+    where
+        T: Default,
+        u64: Clone,
+        &'a str: Clone,
+        String: Clone,
+        u32: Clone,
+        [T; N]: Clone,
+    {
+        User {
+            id: self.id.clone(),
+            r#type: self.r#type.clone(),
+            nickname: self.nickname.clone(),
+            score: self.score.clone(),
+            tags: self.tags.clone(),
+            secret: secret,
+        }
+    }
+    ///Converts this partial struct into the full struct, calling the given closure for the omitted fields as a tuple in declaration order. The closure only runs here, so costly values are computed only when the full struct is rebuilt.
+    #[inline]
+    pub fn to_user_with<__PartialFn>(
+        self,
+        __partial_omitted: __PartialFn,
+    ) -> User<'a, T, N>
+    where
+        __PartialFn: FnOnce() -> (Vec<u8>,),
+        T: Default,
+    {
+        let (secret,) = __partial_omitted();
+        self.to_user(secret)
+    }
+    ///Applies this partial struct on top of `current`: included fields are always taken from this struct, optional fields only when `Some`, and omitted fields are kept from `current`.
+    #[inline]
+    pub fn to_user_or_current(self, current: User<'a, T, N>) -> User<'a, T, N>
+    where
+        T: Default,
+    {
+        User {
+            id: self.id,
+            r#type: self.r#type,
+            nickname: self.nickname,
+            score: self.score,
+            tags: self.tags,
+            secret: current.secret,
+        }
+    }
+    ///Rebuilds the full struct, taking the omitted fields (and any `None` optional fields) from `base`. Same as [`Self::to_user_or_current`].
+    #[inline]
+    pub fn to_user_from_base(self, base: User<'a, T, N>) -> User<'a, T, N>
+    where
+        T: Default,
+    {
+        self.to_user_or_current(base)
+    }
+    ///Writes this partial struct into `target`: included fields are always assigned, optional fields only when `Some`. Omitted fields are left untouched.
+    #[inline]
+    pub fn merge_into(self, target: &mut User<'a, T, N>)
+    where
+        T: Default,
+    {
+        target.id = self.id;
+        target.r#type = self.r#type;
+        target.nickname = self.nickname;
+        target.score = self.score;
+        target.tags = self.tags;
+    }
+    ///Returns the names of the fields whose value differs from `full`, in declaration order. Optional fields are only compared when `Some`; omitted fields are ignored.
+    pub fn diff(&self, full: &User<'a, T, N>) -> ::std::vec::Vec<&'static str>
+    where
+        T: Default,
+        for<'__partial> u64: ::core::cmp::PartialEq,
+        for<'__partial> &'a str: ::core::cmp::PartialEq,
+        for<'__partial> String: ::core::cmp::PartialEq,
+        for<'__partial> u32: ::core::cmp::PartialEq,
+        for<'__partial> [T; N]: ::core::cmp::PartialEq,
+    {
+        let mut changed = ::std::vec::Vec::new();
+        if self.id != full.id {
+            changed.push("id");
+        }
+        if self.r#type != full.r#type {
+            changed.push("type");
+        }
+        if self.nickname != full.nickname {
+            changed.push("nickname");
+        }
+        if self.score != full.score {
+            changed.push("score");
+        }
+        if self.tags != full.tags {
+            changed.push("tags");
+        }
+        changed
+    }
+    ///Splits the full struct into this partial struct and a struct containing the omitted fields.
+    #[inline]
+    pub fn from_user_with_omitted(full: User<'a, T, N>) -> (Self, ProjectionOmitted)
+    where
+        T: Default,
+    {
+        let User { id, r#type, nickname, score, tags, secret } = full;
+        (
+            Self {
+                id: id,
+                r#type: r#type,
+                nickname: nickname,
+                score: score,
+                tags: tags,
+            },
+            ProjectionOmitted { secret },
+        )
+    }
+    ///Converts this partial struct into the full struct, taking the omitted fields from the struct returned by the split methods. Optional fields fall back to the given values when `None`.
+    #[inline]
+    pub fn take_omitted(self, __partial_omitted: ProjectionOmitted) -> User<'a, T, N>
+    where
+        T: Default,
+    {
+        let ProjectionOmitted { secret } = __partial_omitted;
+        self.to_user(secret)
+    }
+    ///Returns the omitted fields filled from their `default(...)` entries, or `Default` for the others, ready for `take_omitted`.
+    #[inline]
+    pub fn omitted_defaults() -> ProjectionOmitted
+    where
+        T: Default,
+        for<'__partial> Vec<u8>: ::core::default::Default,
+    {
+        let secret = ::core::default::Default::default();
+        ProjectionOmitted { secret }
+    }
+    ///Folds `f` over the names of the optional fields that are `Some`, in declaration order.
+    #[inline]
+    pub fn fold_set_optionals<__PartialAcc>(
+        &self,
+        init: __PartialAcc,
+        f: impl FnMut(__PartialAcc, &'static str) -> __PartialAcc,
+    ) -> __PartialAcc {
+        let _ = f;
+        init
+    }
+    ///Returns the names of the optional fields that are `Some`, in declaration order.
+    pub fn present_optionals(&self) -> ::std::vec::Vec<&'static str> {
+        self.fold_set_optionals(
+            ::std::vec::Vec::new(),
+            |mut present, name| {
+                present.push(name);
+                present
+            },
+        )
+    }
+    ///Returns the names of the optional fields that are `None`, in declaration order.
+    pub fn absent_optionals(&self) -> ::std::vec::Vec<&'static str> {
+        ::std::vec::Vec::new()
+    }
+    ///Returns the name and `Display`-formatted value of every optional field that is `Some`, in declaration order (for example to build an SQL `UPDATE ... SET` clause).
+    pub fn changed(&self) -> ::std::vec::Vec<(&'static str, ::std::string::String)> {
+        ::std::vec::Vec::new()
+    }
+    ///Calls `f` with the name and value of every field of this partial struct, in declaration order, for logging any partial generically.
+    pub fn for_each_field<__PartialFn: FnMut(&'static str, &dyn ::core::fmt::Debug)>(
+        &self,
+        mut f: __PartialFn,
+    )
+    where
+        for<'__partial> u64: ::core::fmt::Debug,
+        for<'__partial> &'a str: ::core::fmt::Debug,
+        for<'__partial> String: ::core::fmt::Debug,
+        for<'__partial> u32: ::core::fmt::Debug,
+        for<'__partial> [T; N]: ::core::fmt::Debug,
+    {
+        f("id", &self.id);
+        f("type", &self.r#type);
+        f("nickname", &self.nickname);
+        f("score", &self.score);
+        f("tags", &self.tags);
+    }
+    ///Builds the partial struct from its fields in declaration order, each of the type it is stored as (optional fields as their `Option` or wrapper).
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_parts(
+        id: u64,
+        r#type: &'a str,
+        nickname: String,
+        score: u32,
+        tags: [T; N],
+    ) -> Self {
+        Self {
+            id,
+            r#type,
+            nickname,
+            score,
+            tags,
+        }
+    }
+    ///Passes this partial struct through `f` when `cond` is true, and returns it unchanged otherwise.
+    #[inline]
+    pub fn apply_if(self, cond: bool, f: impl FnOnce(Self) -> Self) -> Self {
+        if cond { f(self) } else { self }
+    }
+    ///The name of the struct this partial struct was derived from.
+    pub const SOURCE_STRUCT: &'static str = "User";
+    ///Returns the names of the fields kept in this partial struct, in declaration order.
+    #[inline]
+    pub const fn included_field_names() -> &'static [&'static str] {
+        &["id", "type", "nickname", "score", "tags"]
+    }
+    ///Returns the names of the fields omitted from this partial struct, in declaration order.
+    #[inline]
+    pub const fn omitted_field_names() -> &'static [&'static str] {
+        &["secret"]
+    }
+    ///Describes the fields of this partial struct, in declaration order. Optional fields report the type they wrap.
+    pub fn schema() -> Vec<::partial_struct::FieldSchema> {
+        ::std::vec![
+            ::partial_struct::FieldSchema { name : "id", type_name :
+            ::core::stringify!(u64), optional : false, }, ::partial_struct::FieldSchema {
+            name : "type", type_name : ::core::stringify!(& 'a str), optional : false, },
+            ::partial_struct::FieldSchema { name : "nickname", type_name :
+            ::core::stringify!(String), optional : false, },
+            ::partial_struct::FieldSchema { name : "score", type_name :
+            ::core::stringify!(u32), optional : false, }, ::partial_struct::FieldSchema {
+            name : "tags", type_name : ::core::stringify!([T; N]), optional : false, }
+        ]
+    }
+}
+///Converts the full struct into this partial struct by projecting the included fields.
+impl<'a, T: Clone, const N: usize> From<User<'a, T, N>> for Projection<'a, T, N>
+where
+    T: Default,
+{
+    #[inline]
+    fn from(full: User<'a, T, N>) -> Self {
+        Self {
+            id: full.id,
+            r#type: full.r#type,
+            nickname: full.nickname,
+            score: full.score,
+            tags: full.tags,
+        }
+    }
+}
+impl ProjectionOmitted {
+    ///Rebuilds the full struct from these omitted fields and the given partial. Same as [`Projection::take_omitted`].
+    #[must_use]
+    #[inline]
+    pub fn into_user<'a, T: Clone, const N: usize>(
+        self,
+        __partial_partial: Projection<'a, T, N>,
+    ) -> User<'a, T, N>
+    where
+        T: Default,
+    {
+        __partial_partial.take_omitted(self)
+    }
+}
+///Converts this partial struct into a structurally identical type, field by field.
+impl<'a, T: Clone, const N: usize> From<Projection<'a, T, N>> for OtherUser<'a, T, N>
+where
+    T: Default,
+{
+    #[inline]
+    fn from(partial: Projection<'a, T, N>) -> Self {
+        Self {
+            id: partial.id,
+            r#type: partial.r#type,
+            nickname: partial.nickname,
+            score: partial.score,
+            tags: partial.tags,
+        }
+    }
+}
+impl<'a, T: Clone, const N: usize> User<'a, T, N>
+where
+    T: Default,
+{
+    ///Splits this struct into its partial representation and a struct containing the omitted fields.
+    #[inline]
+    pub fn into_projection_with_omitted(
+        self,
+    ) -> (Projection<'a, T, N>, ProjectionOmitted) {
+        Projection::from_user_with_omitted(self)
+    }
+    ///Copies the fields of a `Projection` onto this struct in place: included fields always, optional fields only when `Some`. Omitted fields are left untouched.
+    #[inline]
+    pub fn apply_projection(&mut self, partial: Projection<'a, T, N>) {
+        partial.merge_into(self)
+    }
+}
+impl<'a, T: Clone, const N: usize> Draft<'a, T, N>
+where
+    T: Default,
+{
+    ///Converts this partial into a [`Summary`] by rebuilding the full `User` with [`Self::to_user`] and projecting it.
+    #[inline]
+    pub fn into_summary(
+        self,
+        id: u64,
+        secret: Vec<u8>,
+        nickname: Option<String>,
+    ) -> Summary<'a>
+    where
+        T: Default,
+    {
+        <Summary<
+            'a,
+        > as ::core::convert::From<
+            User<'a, T, N>,
+        >>::from(self.to_user(id, secret, nickname))
+    }
+    ///Converts this partial into a [`Patch`] by rebuilding the full `User` with [`Self::to_user`] and projecting it.
+    #[inline]
+    pub fn into_patch(
+        self,
+        id: u64,
+        secret: Vec<u8>,
+        nickname: Option<String>,
+    ) -> Patch<'a, T, N>
+    where
+        T: Default,
+    {
+        <Patch<
+            'a,
+            T,
+            N,
+        > as ::core::convert::From<
+            User<'a, T, N>,
+        >>::from(self.to_user(id, secret, nickname))
+    }
+    ///Converts this partial into a [`Projection`] by rebuilding the full `User` with [`Self::to_user`] and projecting it.
+    #[inline]
+    pub fn into_projection(
+        self,
+        id: u64,
+        secret: Vec<u8>,
+        nickname: Option<String>,
+    ) -> Projection<'a, T, N>
+    where
+        T: Default,
+    {
+        <Projection<
+            'a,
+            T,
+            N,
+        > as ::core::convert::From<
+            User<'a, T, N>,
+        >>::from(self.to_user(id, secret, nickname))
+    }
+}
+impl<'a> Summary<'a> {
+    ///Converts this partial into a [`Draft`] by rebuilding the full `User` with [`Self::to_user`] and projecting it.
+    #[inline]
+    pub fn into_draft<T: Clone, const N: usize>(
+        self,
+        score: u32,
+        tags: [T; N],
+        secret: Vec<u8>,
+    ) -> Draft<'a, T, N>
+    where
+        T: Default,
+    {
+        <Draft<
+            'a,
+            T,
+            N,
+        > as ::core::convert::From<
+            User<'a, T, N>,
+        >>::from(self.to_user(score, tags, secret))
+    }
+    ///Converts this partial into a [`Patch`] by rebuilding the full `User` with [`Self::to_user`] and projecting it.
+    #[inline]
+    pub fn into_patch<T: Clone, const N: usize>(
+        self,
+        score: u32,
+        tags: [T; N],
+        secret: Vec<u8>,
+    ) -> Patch<'a, T, N>
+    where
+        T: Default,
+    {
+        <Patch<
+            'a,
+            T,
+            N,
+        > as ::core::convert::From<
+            User<'a, T, N>,
+        >>::from(self.to_user(score, tags, secret))
+    }
+    ///Converts this partial into a [`Projection`] by rebuilding the full `User` with [`Self::to_user`] and projecting it.
+    #[inline]
+    pub fn into_projection<T: Clone, const N: usize>(
+        self,
+        score: u32,
+        tags: [T; N],
+        secret: Vec<u8>,
+    ) -> Projection<'a, T, N>
+    where
+        T: Default,
+    {
+        <Projection<
+            'a,
+            T,
+            N,
+        > as ::core::convert::From<
+            User<'a, T, N>,
+        >>::from(self.to_user(score, tags, secret))
+    }
+}
+impl<'a, T: Clone, const N: usize> Patch<'a, T, N>
+where
+    T: Default,
+{
+    ///Converts this partial into a [`Draft`] by rebuilding the full `User` with [`Self::apply_to`] and projecting it.
+    #[inline]
+    pub fn into_draft(
+        self,
+        id: u64,
+        r#type: Option<&'a str>,
+        nickname: Option<String>,
+        score: Option<u32>,
+        tags: Option<[T; N]>,
+        secret: Option<Vec<u8>>,
+    ) -> Draft<'a, T, N>
+    where
+        T: Default,
+    {
+        <Draft<
+            'a,
+            T,
+            N,
+        > as ::core::convert::From<
+            User<'a, T, N>,
+        >>::from(self.apply_to(id, r#type, nickname, score, tags, secret))
+    }
+    ///Converts this partial into a [`Summary`] by rebuilding the full `User` with [`Self::apply_to`] and projecting it.
+    #[inline]
+    pub fn into_summary(
+        self,
+        id: u64,
+        r#type: Option<&'a str>,
+        nickname: Option<String>,
+        score: Option<u32>,
+        tags: Option<[T; N]>,
+        secret: Option<Vec<u8>>,
+    ) -> Summary<'a>
+    where
+        T: Default,
+    {
+        <Summary<
+            'a,
+        > as ::core::convert::From<
+            User<'a, T, N>,
+        >>::from(self.apply_to(id, r#type, nickname, score, tags, secret))
+    }
+    ///Converts this partial into a [`Projection`] by rebuilding the full `User` with [`Self::apply_to`] and projecting it.
+    #[inline]
+    pub fn into_projection(
+        self,
+        id: u64,
+        r#type: Option<&'a str>,
+        nickname: Option<String>,
+        score: Option<u32>,
+        tags: Option<[T; N]>,
+        secret: Option<Vec<u8>>,
+    ) -> Projection<'a, T, N>
+    where
+        T: Default,
+    {
+        <Projection<
+            'a,
+            T,
+            N,
+        > as ::core::convert::From<
+            User<'a, T, N>,
+        >>::from(self.apply_to(id, r#type, nickname, score, tags, secret))
+    }
+}
+impl<'a, T: Clone, const N: usize> Projection<'a, T, N>
+where
+    T: Default,
+{
+    ///Converts this partial into a [`Draft`] by rebuilding the full `User` with [`Self::to_user`] and projecting it.
+    #[inline]
+    pub fn into_draft(self, secret: Vec<u8>) -> Draft<'a, T, N>
+    where
+        T: Default,
+    {
+        <Draft<
+            'a,
+            T,
+            N,
+        > as ::core::convert::From<User<'a, T, N>>>::from(self.to_user(secret))
+    }
+    ///Converts this partial into a [`Summary`] by rebuilding the full `User` with [`Self::to_user`] and projecting it.
+    #[inline]
+    pub fn into_summary(self, secret: Vec<u8>) -> Summary<'a>
+    where
+        T: Default,
+    {
+        <Summary<
+            'a,
+        > as ::core::convert::From<User<'a, T, N>>>::from(self.to_user(secret))
+    }
+    ///Converts this partial into a [`Patch`] by rebuilding the full `User` with [`Self::to_user`] and projecting it.
+    #[inline]
+    pub fn into_patch(self, secret: Vec<u8>) -> Patch<'a, T, N>
+    where
+        T: Default,
+    {
+        <Patch<
+            'a,
+            T,
+            N,
+        > as ::core::convert::From<User<'a, T, N>>>::from(self.to_user(secret))
+    }
+}
@@ -0,0 +1,33 @@
+#![deny(dead_code)]
+
+use partial_struct::Partial;
+
+#[allow(clippy::duplicated_attributes)]
+#[derive(Partial)]
+#[partial(
+    "ReportDraft",
+    omit(id),
+    optional(title),
+    field_enum,
+    view,
+    allow_dead_code
+)]
+#[partial("ReportSummary", omit(body), allow_dead_code)]
+struct Report {
+    id: u32,
+    title: String,
+    body: String,
+}
+
+#[test]
+fn never_used_partials_do_not_warn() {
+    let report = Report {
+        id: 1,
+        title: "Q3".to_string(),
+        body: "Revenue grew.".to_string(),
+    };
+    assert_eq!(
+        (report.id, report.title.as_str(), report.body.as_str()),
+        (1, "Q3", "Revenue grew.")
+    );
+}
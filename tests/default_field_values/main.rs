@@ -0,0 +1,7 @@
+//! Default field values are unstable, and even a `#[cfg]`-disabled struct
+//! using them fails to parse on stable, so the tests live in a module that is
+//! only loaded with `RUSTFLAGS="--cfg nightly" cargo +nightly test`.
+#![cfg_attr(nightly, feature(default_field_values))]
+
+#[cfg(nightly)]
+mod values;
@@ -0,0 +1,65 @@
+use partial_struct::Partial;
+
+#[derive(Partial, Debug, PartialEq)]
+#[partial(
+    "RetryPolicyDraft",
+    derive(Debug, PartialEq),
+    omit(id),
+    optional(jitter)
+)]
+struct RetryPolicy {
+    id: u32,
+    attempts: u8 = 3,
+    backoff_ms: Vec<u64> = Vec::new(),
+    jitter: bool = true,
+}
+
+#[test]
+fn partial_keeps_the_default_field_values() {
+    let draft = RetryPolicyDraft {
+        backoff_ms: vec![50],
+        jitter: None,
+        ..
+    };
+    assert_eq!(
+        draft,
+        RetryPolicyDraft {
+            attempts: 3,
+            backoff_ms: vec![50],
+            jitter: None,
+        }
+    );
+    assert_eq!(
+        draft.to_retry_policy(7, Some(false)),
+        RetryPolicy {
+            id: 7,
+            jitter: false,
+            backoff_ms: vec![50],
+            ..
+        }
+    );
+}
+
+partial_struct::partial_for! {
+    #[derive(Debug, PartialEq)]
+    #[partial("LimitsPatch", derive(Debug, PartialEq), omit(burst))]
+    struct Limits {
+        rate: u32 = 10,
+        burst: u32 = 20,
+    }
+}
+
+#[test]
+fn partial_for_keeps_the_default_field_values_on_both_structs() {
+    assert_eq!(
+        Limits { .. },
+        Limits {
+            rate: 10,
+            burst: 20
+        }
+    );
+    assert_eq!(
+        LimitsPatch { .. }.to_limits(5),
+        Limits { rate: 10, burst: 5 }
+    );
+}
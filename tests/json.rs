@@ -0,0 +1,82 @@
+#![cfg(feature = "json")]
+
+use partial_struct::Partial;
+
+#[derive(Partial, Debug)]
+#[partial(
+    "SignupForm",
+    derive(Debug, PartialEq),
+    omit(id),
+    optional(email, referrer),
+    from_json
+)]
+struct Signup {
+    #[allow(dead_code)]
+    id: u64,
+    name: String,
+    age: u8,
+    email: String,
+    referrer: String,
+}
+
+#[test]
+fn from_json_value_tolerates_missing_optional_fields() {
+    let value = serde_json::json!({ "name": "Ada", "age": 36, "referrer": null });
+    let form = SignupForm::from_json_value(&value).unwrap();
+    assert_eq!(
+        form,
+        SignupForm {
+            name: "Ada".to_string(),
+            age: 36,
+            email: None,
+            referrer: None,
+        }
+    );
+
+    let value = serde_json::json!({ "name": "Ada", "age": 36, "email": "ada@example.com" });
+    let form = SignupForm::from_json_value(&value).unwrap();
+    assert_eq!(form.email.as_deref(), Some("ada@example.com"));
+}
+
+#[test]
+fn from_json_value_reports_missing_and_mistyped_fields() {
+    let missing = SignupForm::from_json_value(&serde_json::json!({ "name": "Ada" })).unwrap_err();
+    assert_eq!(missing, "missing field `age`");
+
+    let mistyped = SignupForm::from_json_value(&serde_json::json!({ "name": "Ada", "age": "old" }))
+        .unwrap_err();
+    assert!(mistyped.starts_with("field `age`: "), "{}", mistyped);
+
+    let not_object = SignupForm::from_json_value(&serde_json::json!([1, 2])).unwrap_err();
+    assert_eq!(not_object, "expected a JSON object for `SignupForm`");
+}
+
+mod result_alias {
+    use partial_struct::Partial;
+
+    // A crate-wide alias like this must not change the generated signatures.
+    #[allow(dead_code)]
+    type Result<T> = std::result::Result<T, String>;
+
+    #[derive(Partial, Debug)]
+    #[partial("NewsletterForm", derive(Debug, PartialEq), optional(topic), from_json)]
+    pub struct Newsletter {
+        pub address: String,
+        pub topic: String,
+    }
+}
+
+#[test]
+fn from_json_value_ignores_a_result_alias_in_scope() {
+    let form = result_alias::NewsletterForm::from_json_value(
+        &serde_json::json!({ "address": "ada@example.com" }),
+    )
+    .unwrap();
+    assert_eq!(
+        form,
+        result_alias::NewsletterForm {
+            address: "ada@example.com".to_string(),
+            topic: None,
+        }
+    );
+}
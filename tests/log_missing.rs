@@ -0,0 +1,95 @@
+#![cfg(feature = "log")]
+
+use partial_struct::Partial;
+use std::sync::Mutex;
+
+static MESSAGES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+struct CaptureLogger;
+
+impl log::Log for CaptureLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::Level::Warn
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            MESSAGES.lock().unwrap().push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: CaptureLogger = CaptureLogger;
+
+#[derive(Partial, Debug, PartialEq)]
+#[partial(omit(id), optional(email, phone), log_missing)]
+struct User {
+    id: u32,
+    name: String,
+    email: String,
+    phone: String,
+}
+
+#[test]
+fn missing_optional_is_logged_and_returned() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(log::LevelFilter::Warn);
+
+    let partial = PartialUser {
+        name: "Ada".to_string(),
+        email: Some("ada@example.com".to_string()),
+        phone: None,
+    };
+    assert_eq!(partial.to_user_logged_missing(1), Err("phone"));
+    assert_eq!(
+        MESSAGES.lock().unwrap().as_slice(),
+        ["cannot rebuild `User`: optional field `phone` is missing"]
+    );
+
+    let partial = PartialUser {
+        name: "Ada".to_string(),
+        email: Some("ada@example.com".to_string()),
+        phone: Some("555".to_string()),
+    };
+    assert_eq!(
+        partial.to_user_logged_missing(1),
+        Ok(User {
+            id: 1,
+            name: "Ada".to_string(),
+            email: "ada@example.com".to_string(),
+            phone: "555".to_string(),
+        })
+    );
+    assert_eq!(MESSAGES.lock().unwrap().len(), 1);
+}
+
+mod result_alias {
+    use partial_struct::Partial;
+
+    // A crate-wide alias like this must not change the generated signatures.
+    #[allow(dead_code)]
+    type Result<T> = std::result::Result<T, String>;
+
+    #[derive(Partial, Debug, PartialEq)]
+    #[partial(omit(id), optional(note), log_missing)]
+    pub struct Entry {
+        pub id: u32,
+        pub note: String,
+    }
+}
+
+#[test]
+fn log_missing_ignores_a_result_alias_in_scope() {
+    let partial = result_alias::PartialEntry {
+        note: Some("ok".to_string()),
+    };
+    assert_eq!(
+        partial.to_entry_logged_missing(2),
+        Ok(result_alias::Entry {
+            id: 2,
+            note: "ok".to_string(),
+        })
+    );
+}
@@ -1,4 +1,5 @@
 use partial_struct::Partial;
+use serde::Serialize;
 
 #[derive(Partial, Debug, PartialEq)]
 #[partial(derive(Debug, PartialEq), omit(id), optional(email))]
@@ -90,3 +91,2717 @@ fn split_with_multiple_omitted_fields() {
     assert_eq!(omitted.a, 1);
     assert_eq!(omitted.b, 2);
 }
+
+#[derive(Partial, Debug, PartialEq)]
+#[partial(derive(Debug, PartialEq), omit(id))]
+struct Borrowed<'a> {
+    name: &'a str,
+    id: u32,
+}
+
+#[test]
+fn lifetime_is_forwarded_to_partial() {
+    let name = String::from("Grace");
+    let full = Borrowed { name: &name, id: 3 };
+
+    let partial: PartialBorrowed<'_> = full.into();
+    assert_eq!(partial, PartialBorrowed { name: "Grace" });
+
+    let rebuilt = partial.to_borrowed(3);
+    assert_eq!(rebuilt, Borrowed { name: &name, id: 3 });
+
+    let (partial, omitted) = rebuilt.into_partial_borrowed_with_omitted();
+    assert_eq!(partial.name, "Grace");
+    assert_eq!(omitted.id, 3);
+}
+
+#[derive(Partial, Debug, PartialEq)]
+#[partial(derive(Debug, PartialEq), omit(id), optional(email), from_shared)]
+struct Shared {
+    id: u32,
+    name: String,
+    email: String,
+}
+
+#[test]
+fn partial_from_shared_original() {
+    let full = std::sync::Arc::new(Shared {
+        id: 5,
+        name: "Kay".to_string(),
+        email: "kay@example.com".to_string(),
+    });
+
+    let partial = PartialShared::from(&full);
+    assert_eq!(
+        partial,
+        PartialShared {
+            name: "Kay".to_string(),
+            email: Some("kay@example.com".to_string()),
+        }
+    );
+    assert_eq!(full.id, 5);
+
+    let local = std::rc::Rc::new(Shared {
+        id: 6,
+        name: "Max".to_string(),
+        email: "max@example.com".to_string(),
+    });
+    let partial: PartialShared = (&local).into();
+    assert_eq!(partial.name, "Max");
+    assert_eq!(local.name, "Max");
+}
+
+#[derive(Partial, Debug, PartialEq)]
+#[partial(derive(Debug, PartialEq), omit(id), optional(email), borrowed)]
+struct Profile {
+    id: u32,
+    name: String,
+    email: String,
+}
+
+#[test]
+fn ref_partial_into_owned() {
+    let full = Profile {
+        id: 9,
+        name: "Bo".to_string(),
+        email: "bo@example.com".to_string(),
+    };
+
+    let view = PartialProfileRef::from(&full);
+    assert_eq!(view.name, "Bo");
+    assert_eq!(view.email.map(String::as_str), Some("bo@example.com"));
+
+    let mut owned = view.into_owned();
+    owned.name.push('b');
+    owned.email = None;
+    assert_eq!(
+        owned,
+        PartialProfile {
+            name: "Bob".to_string(),
+            email: None,
+        }
+    );
+    assert_eq!(full.name, "Bo");
+
+    let view = owned.as_partial_ref();
+    assert_eq!(view.name, "Bob");
+    assert_eq!(view.email, None);
+}
+
+#[derive(Partial, Debug, PartialEq)]
+#[partial(
+    derive(Debug, PartialEq),
+    omit(id),
+    optional(email),
+    maps_to = "PartialAccount"
+)]
+struct Member {
+    id: u32,
+    name: String,
+    email: String,
+}
+
+#[derive(Partial, Debug, PartialEq)]
+#[partial(derive(Debug, PartialEq), omit(balance), optional(email))]
+struct Account {
+    name: String,
+    balance: i64,
+    email: String,
+}
+
+#[test]
+fn partial_maps_to_compatible_partial() {
+    let member = PartialMember {
+        name: "Eve".to_string(),
+        email: Some("eve@example.com".to_string()),
+    };
+
+    let account: PartialAccount = member.into();
+    assert_eq!(
+        account,
+        PartialAccount {
+            name: "Eve".to_string(),
+            email: Some("eve@example.com".to_string()),
+        }
+    );
+    assert_eq!(account.to_account(10, None).balance, 10);
+}
+
+#[derive(Partial, Debug, PartialEq)]
+#[partial(derive(Debug), omit(id), optional(email), cross_eq)]
+struct Contact {
+    id: u32,
+    name: String,
+    email: String,
+}
+
+#[test]
+fn partial_compares_against_full_ignoring_omitted() {
+    let partial = PartialContact {
+        name: "Ann".to_string(),
+        email: Some("ann@example.com".to_string()),
+    };
+    let full = Contact {
+        id: 42,
+        name: "Ann".to_string(),
+        email: "ann@example.com".to_string(),
+    };
+
+    assert_eq!(partial, full);
+    assert_eq!(full, partial);
+
+    let unset = PartialContact {
+        name: "Ann".to_string(),
+        email: None,
+    };
+    assert_ne!(unset, full);
+}
+
+#[derive(Partial, Debug, PartialEq)]
+#[partial(derive(Debug, PartialEq, Eq, PartialOrd, Ord), omit(id))]
+struct Player {
+    id: u32,
+    score: u32,
+    #[partial(sort_key)]
+    name: String,
+}
+
+#[test]
+fn sort_key_orders_partials_by_marked_field() {
+    let mut players = [
+        PartialPlayer {
+            score: 1,
+            name: "Cid".to_string(),
+        },
+        PartialPlayer {
+            score: 3,
+            name: "Abe".to_string(),
+        },
+        PartialPlayer {
+            score: 2,
+            name: "Bea".to_string(),
+        },
+    ];
+    players.sort();
+
+    let names: Vec<_> = players.iter().map(|player| player.name.as_str()).collect();
+    assert_eq!(names, ["Abe", "Bea", "Cid"]);
+
+    // Equal sort keys fall back to the other fields, agreeing with `PartialEq`.
+    let low = PartialPlayer {
+        score: 1,
+        name: "Abe".to_string(),
+    };
+    assert_eq!(low.cmp(&players[0]), std::cmp::Ordering::Less);
+    let same = PartialPlayer {
+        score: 3,
+        name: "Abe".to_string(),
+    };
+    assert_eq!(same.cmp(&players[0]), std::cmp::Ordering::Equal);
+}
+
+#[derive(Partial, Debug, PartialEq)]
+#[partial(
+    "HotelListing",
+    derive(Debug, PartialEq, Eq, PartialOrd, Ord),
+    omit(id)
+)]
+struct Hotel {
+    id: u32,
+    #[partial(sort_key)]
+    stars: u8,
+    #[partial_flatten(street: String, city: String)]
+    address: Address,
+}
+
+#[test]
+fn sort_key_ties_compare_flattened_fields() {
+    let listing = |stars: u8, city: &str| HotelListing {
+        stars,
+        street: "1 Main St".to_string(),
+        city: city.to_string(),
+    };
+    let mut listings = [listing(4, "Oslo"), listing(3, "Rome"), listing(4, "Lima")];
+    listings.sort();
+
+    let order: Vec<_> = listings
+        .iter()
+        .map(|listing| (listing.stars, listing.city.as_str()))
+        .collect();
+    assert_eq!(order, [(3, "Rome"), (4, "Lima"), (4, "Oslo")]);
+}
+
+#[test]
+fn field_names_in_declaration_order() {
+    assert_eq!(PartialUser::included_field_names(), ["name", "email"]);
+    assert_eq!(PartialUser::omitted_field_names(), ["id"]);
+
+    assert_eq!(PartialMultiOmit::included_field_names(), ["c", "d"]);
+    assert_eq!(PartialMultiOmit::omitted_field_names(), ["a", "b"]);
+
+    assert_eq!(PartialPoint::included_field_names(), ["x", "y"]);
+    assert!(PartialPoint::omitted_field_names().is_empty());
+}
+
+#[test]
+fn apply_partial_onto_current() {
+    let current = MultiOmit {
+        a: 1,
+        b: 2,
+        c: 3,
+        d: 4,
+    };
+
+    let unchanged = PartialMultiOmit { c: None, d: 4 }.to_multi_omit_or_current(current);
+    assert_eq!(
+        unchanged,
+        MultiOmit {
+            a: 1,
+            b: 2,
+            c: 3,
+            d: 4,
+        }
+    );
+
+    let updated = PartialMultiOmit { c: Some(30), d: 4 }.to_multi_omit_or_current(unchanged);
+    assert_eq!(
+        updated,
+        MultiOmit {
+            a: 1,
+            b: 2,
+            c: 30,
+            d: 4,
+        }
+    );
+}
+
+#[derive(Partial, Debug, PartialEq)]
+#[partial(
+    "SettingsPatch",
+    derive(Debug, Default, PartialEq),
+    omit(id),
+    all_optional
+)]
+struct Settings {
+    id: u32,
+    theme: String,
+    font_size: u8,
+    notifications: bool,
+}
+
+#[test]
+fn all_optional_patch_merges_only_set_fields() {
+    let mut settings = Settings {
+        id: 1,
+        theme: "light".to_string(),
+        font_size: 12,
+        notifications: true,
+    };
+
+    let patch = SettingsPatch {
+        font_size: Some(16),
+        ..SettingsPatch::default()
+    };
+    assert_eq!(patch.theme, None);
+    patch.merge_into(&mut settings);
+
+    assert_eq!(
+        settings,
+        Settings {
+            id: 1,
+            theme: "light".to_string(),
+            font_size: 16,
+            notifications: true,
+        }
+    );
+
+    let rebuilt = SettingsPatch {
+        theme: Some("dark".to_string()),
+        ..SettingsPatch::default()
+    }
+    .to_settings(2, None, Some(10), Some(false));
+    assert_eq!(rebuilt.theme, "dark");
+    assert_eq!(rebuilt.font_size, 10);
+}
+
+#[test]
+fn overlay_layers_patches_last_writer_wins() {
+    let defaults = SettingsPatch {
+        theme: Some("light".to_string()),
+        font_size: Some(12),
+        notifications: Some(true),
+    };
+    let user = SettingsPatch {
+        theme: Some("dark".to_string()),
+        font_size: Some(14),
+        ..SettingsPatch::default()
+    };
+    let admin = SettingsPatch {
+        font_size: Some(16),
+        ..SettingsPatch::default()
+    };
+
+    let expected = SettingsPatch {
+        theme: Some("dark".to_string()),
+        font_size: Some(16),
+        notifications: Some(true),
+    };
+
+    let mut extended = SettingsPatch::default();
+    extended.extend([defaults, user, admin]);
+    assert_eq!(extended, expected);
+
+    let layered = SettingsPatch::default()
+        .overlay(SettingsPatch {
+            notifications: Some(true),
+            ..SettingsPatch::default()
+        })
+        .overlay(SettingsPatch {
+            notifications: Some(false),
+            ..SettingsPatch::default()
+        })
+        .overlay(SettingsPatch::default());
+    assert_eq!(layered.notifications, Some(false));
+}
+
+mod scoped {
+    use partial_struct::Partial;
+
+    #[derive(Partial, Debug, PartialEq)]
+    #[partial(
+        derive(Debug, PartialEq),
+        omit(id),
+        optional(email),
+        cross_eq,
+        scoped_impls
+    )]
+    pub struct Account {
+        pub id: u32,
+        pub name: String,
+        pub email: String,
+    }
+}
+
+#[test]
+fn scoped_impls_keep_public_items_reachable() {
+    let full = scoped::Account {
+        id: 4,
+        name: "Ivy".to_string(),
+        email: "ivy@example.com".to_string(),
+    };
+
+    let (partial, omitted): (scoped::PartialAccount, scoped::PartialAccountOmitted) =
+        full.into_partial_account_with_omitted();
+    assert_eq!(partial.name, "Ivy");
+    assert_eq!(omitted.id, 4);
+
+    let rebuilt = partial.to_account(omitted.id, None);
+    let partial: scoped::PartialAccount = rebuilt.into();
+    assert_eq!(
+        partial,
+        scoped::Account {
+            id: 0,
+            name: "Ivy".to_string(),
+            email: "ivy@example.com".to_string(),
+        }
+    );
+}
+
+#[derive(Partial, Debug, PartialEq)]
+#[partial(derive(Debug, PartialEq), omit(r#type), optional(r#match))]
+struct Token {
+    r#type: String,
+    r#match: String,
+    value: u32,
+}
+
+#[test]
+fn raw_identifier_fields() {
+    let full = Token {
+        r#type: "keyword".to_string(),
+        r#match: "fn".to_string(),
+        value: 1,
+    };
+
+    let (partial, omitted) = full.into_partial_token_with_omitted();
+    assert_eq!(partial.r#match.as_deref(), Some("fn"));
+    assert_eq!(omitted.r#type, "keyword");
+    assert_eq!(PartialToken::included_field_names(), ["match", "value"]);
+    assert_eq!(PartialToken::omitted_field_names(), ["type"]);
+
+    let rebuilt = partial.to_token(omitted.r#type, None);
+    assert_eq!(rebuilt.r#type, "keyword");
+    assert_eq!(rebuilt.r#match, "fn");
+}
+
+#[test]
+fn apply_if_runs_closure_only_when_condition_holds() {
+    let base = || PartialUser {
+        name: "Ada".to_string(),
+        email: None,
+    };
+    let set_email = |mut partial: PartialUser| {
+        partial.email = Some("ada@example.com".to_string());
+        partial
+    };
+
+    let applied = base().apply_if(true, set_email);
+    assert_eq!(applied.email.as_deref(), Some("ada@example.com"));
+
+    let skipped = base().apply_if(false, set_email);
+    assert_eq!(skipped, base());
+}
+
+#[test]
+fn take_omitted_round_trip() {
+    let full = MultiOmit {
+        a: 1,
+        b: 2,
+        c: 3,
+        d: 4,
+    };
+
+    let (partial, omitted) = full.into_partial_multi_omit_with_omitted();
+    let rebuilt = partial.take_omitted(omitted, None);
+    assert_eq!(
+        rebuilt,
+        MultiOmit {
+            a: 1,
+            b: 2,
+            c: 3,
+            d: 4,
+        }
+    );
+
+    let (partial, omitted) = Point { x: 5, y: 6 }.into_partial_point_with_omitted();
+    assert_eq!(partial.take_omitted(omitted), Point { x: 5, y: 6 });
+}
+
+#[derive(Partial, Debug, PartialEq)]
+#[partial(derive(Debug))]
+pub struct Size {
+    pub width: u32,
+    pub height: u32,
+}
+
+fn finish<P: partial_struct::PartialComplete>(partial: P) -> P::Full {
+    partial.complete()
+}
+
+#[test]
+fn complete_through_trait_without_omitted_fields() {
+    let full = finish(PartialSize {
+        width: 7,
+        height: 8,
+    });
+    assert_eq!(
+        full,
+        Size {
+            width: 7,
+            height: 8,
+        }
+    );
+}
+
+#[allow(clippy::duplicated_attributes)]
+#[derive(Partial, Debug, PartialEq)]
+#[partial(derive(Debug, Clone, Debug, Copy, PartialEq, Clone), omit(label))]
+struct Pixel {
+    label: String,
+    x: u16,
+    y: u16,
+}
+
+#[test]
+fn duplicate_derives_are_deduplicated() {
+    let partial = PartialPixel { x: 1, y: 2 };
+    let copy = partial;
+    assert_eq!(partial, copy);
+    assert_eq!(format!("{:?}", copy), "PartialPixel { x: 1, y: 2 }");
+}
+
+#[derive(Partial, Debug, PartialEq)]
+#[partial(
+    derive(Debug, PartialEq, Serialize),
+    omit(id),
+    optional(nickname),
+    tuple_struct
+)]
+struct Row {
+    id: u32,
+    nickname: String,
+    name: String,
+}
+
+#[test]
+fn tuple_struct_serializes_positionally() {
+    let partial = PartialRow {
+        name: "Ada".to_string(),
+        nickname: None,
+    };
+
+    let tuple = partial.into_tuple_struct();
+    assert_eq!(tuple, PartialRowTuple(None, "Ada".to_string()));
+    assert_eq!(serde_json::to_string(&tuple).unwrap(), r#"[null,"Ada"]"#);
+}
+
+#[test]
+fn serialize_skips_none_optional_fields() {
+    let partial = PartialRow {
+        name: "Ada".to_string(),
+        nickname: None,
+    };
+    assert_eq!(
+        serde_json::to_string(&partial).unwrap(),
+        r#"{"name":"Ada"}"#
+    );
+
+    let partial = PartialRow {
+        nickname: Some("ada".to_string()),
+        ..partial
+    };
+    assert_eq!(
+        serde_json::to_string(&partial).unwrap(),
+        r#"{"name":"Ada","nickname":"ada"}"#
+    );
+}
+
+#[derive(Partial, Debug, PartialEq)]
+#[partial(derive(Debug), omit(id), optional(role = "user".to_string(), email))]
+struct Staff {
+    id: u32,
+    role: String,
+    email: String,
+}
+
+#[test]
+fn optional_default_used_when_absent() {
+    let partial = PartialStaff {
+        role: None,
+        email: Some("m@example.com".to_string()),
+    };
+    let full = partial.to_staff(1, None);
+    assert_eq!(full.role, "user");
+    assert_eq!(full.email, "m@example.com");
+
+    let partial = PartialStaff {
+        role: Some("admin".to_string()),
+        email: None,
+    };
+    assert_eq!(
+        partial
+            .to_staff_cloned(2, Some("x@example.com".to_string()))
+            .role,
+        "admin"
+    );
+}
+
+#[derive(Partial, Debug, PartialEq)]
+#[partial("Draft", omit(id), rebuild_fn = "publish")]
+#[partial("Summary", omit(body))]
+struct Post {
+    id: u32,
+    title: String,
+    body: String,
+}
+
+#[test]
+fn custom_rebuild_fn_name() {
+    let draft = Draft {
+        title: "Hi".to_string(),
+        body: "...".to_string(),
+    };
+    let cloned = draft.publish_cloned(1);
+    let post = draft.publish(1);
+    assert_eq!(post, cloned);
+
+    let summary = Summary {
+        id: 1,
+        title: "Hi".to_string(),
+    };
+    assert_eq!(summary.to_post("...".to_string()), post);
+}
+
+#[test]
+fn fold_over_set_optional_fields() {
+    let patch = SettingsPatch {
+        theme: Some("dark".to_string()),
+        font_size: None,
+        notifications: Some(false),
+    };
+
+    let joined = patch.fold_set_optionals(String::new(), |mut acc, name| {
+        if !acc.is_empty() {
+            acc.push_str(", ");
+        }
+        acc.push_str(name);
+        acc
+    });
+    assert_eq!(joined, "theme, notifications");
+
+    let point = PartialPoint { x: 1, y: 2 };
+    assert_eq!(point.fold_set_optionals(0, |count, _| count + 1), 0);
+}
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial("PairPatch", optional(first, second))]
+struct Pair<A, B> {
+    first: A,
+    second: B,
+}
+
+#[test]
+fn fold_set_optionals_on_a_partial_with_type_parameter_a() {
+    let patch: PairPatch<u8, &str> = PairPatch {
+        first: None,
+        second: Some("b"),
+    };
+    let set = patch.fold_set_optionals(Vec::new(), |mut acc, name| {
+        acc.push(name);
+        acc
+    });
+    assert_eq!(set, ["second"]);
+}
+
+#[test]
+fn rebuild_from_base_takes_omitted_fields() {
+    let base = User {
+        id: 99,
+        name: "Old".to_string(),
+        email: "old@example.com".to_string(),
+    };
+    let partial = PartialUser {
+        name: "New".to_string(),
+        email: None,
+    };
+
+    let rebuilt = partial.to_user_from_base(base);
+    assert_eq!(
+        rebuilt,
+        User {
+            id: 99,
+            name: "New".to_string(),
+            email: "old@example.com".to_string(),
+        }
+    );
+}
+
+#[derive(Partial, Debug, PartialEq)]
+#[partial(derive(Hash), omit(password), optional(nickname), hash_helper)]
+struct Credentials {
+    username: String,
+    password: String,
+    nickname: String,
+}
+
+#[test]
+fn hash_helper_matches_partial_hash() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let full = Credentials {
+        username: "root".to_string(),
+        password: "hunter2".to_string(),
+        nickname: "admin".to_string(),
+    };
+    let other_password = Credentials {
+        username: "root".to_string(),
+        password: "swordfish".to_string(),
+        nickname: "admin".to_string(),
+    };
+    assert_eq!(full.partial_hash(), other_password.partial_hash());
+
+    let expected = full.partial_hash();
+    let partial: PartialCredentials = full.into();
+    let mut hasher = DefaultHasher::new();
+    partial.hash(&mut hasher);
+    assert_eq!(hasher.finish(), expected);
+}
+
+#[derive(Partial, Debug, PartialEq)]
+#[partial(derive(Debug), omit(id, tags), optional(email, role = "user".to_string()), try_complete)]
+struct Signup {
+    id: u32,
+    tags: Vec<String>,
+    name: String,
+    email: String,
+    role: String,
+}
+
+impl partial_struct::Validate for Signup {
+    fn validate(&self) -> Result<(), String> {
+        if self.email.contains('@') {
+            Ok(())
+        } else {
+            Err(format!("`{}` is not an email address", self.email))
+        }
+    }
+}
+
+#[test]
+fn try_complete_reports_each_failure_mode() {
+    let missing = PartialSignup {
+        name: "Ada".to_string(),
+        email: None,
+        role: None,
+    };
+    assert_eq!(
+        Signup::try_from(missing),
+        Err(PartialSignupCompleteError::MissingField("email"))
+    );
+
+    let invalid = PartialSignup {
+        name: "Ada".to_string(),
+        email: Some("nope".to_string()),
+        role: None,
+    };
+    let err = Signup::try_from(invalid).unwrap_err();
+    assert_eq!(
+        err,
+        PartialSignupCompleteError::Invalid("`nope` is not an email address".to_string())
+    );
+    assert_eq!(
+        err.to_string(),
+        "invalid `Signup`: `nope` is not an email address"
+    );
+
+    let valid = PartialSignup {
+        name: "Ada".to_string(),
+        email: Some("ada@example.com".to_string()),
+        role: None,
+    };
+    assert_eq!(
+        Signup::try_from(valid),
+        Ok(Signup {
+            id: 0,
+            tags: Vec::new(),
+            name: "Ada".to_string(),
+            email: "ada@example.com".to_string(),
+            role: "user".to_string(),
+        })
+    );
+}
+
+#[derive(Partial)]
+#[partial("Creation")]
+#[partial("Update", all_optional)]
+struct Article {
+    #[partial_group(creation)]
+    author: String,
+    title: String,
+    #[partial_group(creation, update)]
+    body: String,
+    #[partial_group(update)]
+    revision: u32,
+}
+
+#[test]
+fn partial_groups_select_fields_per_partial() {
+    assert_eq!(
+        Creation::included_field_names(),
+        ["author", "title", "body"]
+    );
+    assert_eq!(Creation::omitted_field_names(), ["revision"]);
+    assert_eq!(
+        Update::included_field_names(),
+        ["title", "body", "revision"]
+    );
+    assert_eq!(Update::omitted_field_names(), ["author"]);
+
+    let article = Creation {
+        author: "Ada".to_string(),
+        title: "Notes".to_string(),
+        body: "First draft".to_string(),
+    }
+    .to_article(1);
+
+    let update = Update {
+        title: None,
+        body: Some("Second draft".to_string()),
+        revision: Some(2),
+    };
+    let updated = update.to_article_or_current(article);
+    assert_eq!(updated.author, "Ada");
+    assert_eq!(updated.title, "Notes");
+    assert_eq!(updated.body, "Second draft");
+    assert_eq!(updated.revision, 2);
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Address {
+    street: String,
+    city: String,
+}
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial("Resident", derive(Debug, PartialEq), omit(id))]
+struct Inhabitant {
+    id: u32,
+    name: String,
+    #[partial_flatten(street: String, city: String)]
+    address: Address,
+}
+
+#[test]
+fn flatten_inlines_embedded_fields() {
+    let inhabitant = Inhabitant {
+        id: 3,
+        name: "Ada".to_string(),
+        address: Address {
+            street: "1 Main St".to_string(),
+            city: "Springfield".to_string(),
+        },
+    };
+
+    let resident = Resident::from(inhabitant.clone());
+    assert_eq!(
+        resident,
+        Resident {
+            name: "Ada".to_string(),
+            street: "1 Main St".to_string(),
+            city: "Springfield".to_string(),
+        }
+    );
+    assert_eq!(Resident::included_field_names(), ["name", "street", "city"]);
+
+    assert_eq!(resident.to_inhabitant(3), inhabitant);
+}
+
+#[derive(Partial, Debug, PartialEq)]
+#[partial("SessionRequest", omit(id, token))]
+struct Session {
+    id: u32,
+    user: String,
+    token: String,
+}
+
+#[test]
+fn to_with_only_computes_omitted_fields_when_rebuilding() {
+    let calls = std::cell::Cell::new(0);
+    let issue = || {
+        calls.set(calls.get() + 1);
+        (42, "secret".to_string())
+    };
+
+    let requests = [
+        SessionRequest {
+            user: "guest".to_string(),
+        },
+        SessionRequest {
+            user: "ada".to_string(),
+        },
+    ];
+    let sessions: Vec<Session> = requests
+        .into_iter()
+        .filter(|request| request.user != "guest")
+        .map(|request| request.to_session_with(issue))
+        .collect();
+
+    assert_eq!(calls.get(), 1);
+    assert_eq!(
+        sessions,
+        [Session {
+            id: 42,
+            user: "ada".to_string(),
+            token: "secret".to_string(),
+        }]
+    );
+}
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial(derive(Debug, Clone, PartialEq), omit(label))]
+struct Matrix<const N: usize> {
+    data: [f32; N],
+    label: String,
+}
+
+#[test]
+fn const_generic_struct_omitting_a_field() {
+    let matrix = Matrix {
+        data: [1.0, 2.0, 3.0],
+        label: "identity".to_string(),
+    };
+
+    let partial: PartialMatrix<3> = matrix.clone().into();
+    assert_eq!(partial.data, [1.0, 2.0, 3.0]);
+    assert_eq!(partial.to_matrix("identity".to_string()), matrix);
+
+    let (partial, omitted) = PartialMatrix::from_matrix_with_omitted(matrix.clone());
+    assert_eq!(partial.take_omitted(omitted), matrix);
+}
+
+#[test]
+fn diff_lists_changed_fields() {
+    let full = User {
+        id: 1,
+        name: "Ada".to_string(),
+        email: "ada@example.com".to_string(),
+    };
+    let partial = PartialUser {
+        name: "Grace".to_string(),
+        email: None,
+    };
+    assert_eq!(partial.diff(&full), ["name"]);
+
+    let partial = PartialUser {
+        name: "Ada".to_string(),
+        email: Some("ada@example.org".to_string()),
+    };
+    assert_eq!(partial.diff(&full), ["email"]);
+}
+
+#[derive(Partial, Debug, PartialEq)]
+#[partial("ContactCard", omit(id), optional(email, phone), try_rebuild)]
+struct Directory {
+    id: u32,
+    name: String,
+    email: String,
+    phone: String,
+}
+
+#[test]
+fn try_rebuild_reports_the_missing_optional_field() {
+    let full = Directory {
+        id: 5,
+        name: "Ada".to_string(),
+        email: "ada@example.com".to_string(),
+        phone: "555-0100".to_string(),
+    };
+    let (card, omitted) = ContactCard::from_directory_with_omitted(full);
+    let rebuilt = card.try_to_directory(omitted).unwrap();
+    assert_eq!(rebuilt.phone, "555-0100");
+
+    let (mut card, omitted) = ContactCard::from_directory_with_omitted(rebuilt);
+    card.phone = None;
+    let err = card.try_to_directory(omitted).unwrap_err();
+    assert_eq!(err, ContactCardMissingField::Phone);
+    assert_eq!(err.field_name(), "phone");
+    assert_eq!(err.to_string(), "missing optional field `phone`");
+}
+
+#[test]
+fn apply_partial_updates_full_struct_in_place() {
+    let mut user = User {
+        id: 9,
+        name: "Ada".to_string(),
+        email: "ada@example.com".to_string(),
+    };
+
+    user.apply_partial_user(PartialUser {
+        name: "Grace".to_string(),
+        email: None,
+    });
+    assert_eq!(
+        user,
+        User {
+            id: 9,
+            name: "Grace".to_string(),
+            email: "ada@example.com".to_string(),
+        }
+    );
+
+    user.apply_partial_user(PartialUser {
+        name: "Grace".to_string(),
+        email: Some("grace@example.com".to_string()),
+    });
+    assert_eq!(user.id, 9);
+    assert_eq!(user.email, "grace@example.com");
+}
+
+/// A three-state wrapper that tells "not sent" apart from "sent as null".
+#[derive(Debug, Clone, PartialEq)]
+enum Undefinable<T> {
+    Missing,
+    Null,
+    Value(T),
+}
+
+impl<T> partial_struct::OptionLike<T> for Undefinable<T> {
+    fn from_option(value: Option<T>) -> Self {
+        value.map_or(Undefinable::Missing, Undefinable::Value)
+    }
+
+    fn into_option(self) -> Option<T> {
+        match self {
+            Undefinable::Value(value) => Some(value),
+            Undefinable::Missing | Undefinable::Null => None,
+        }
+    }
+
+    fn as_option(&self) -> Option<&T> {
+        match self {
+            Undefinable::Value(value) => Some(value),
+            Undefinable::Missing | Undefinable::Null => None,
+        }
+    }
+
+    fn as_option_mut(&mut self) -> Option<&mut T> {
+        match self {
+            Undefinable::Value(value) => Some(value),
+            Undefinable::Missing | Undefinable::Null => None,
+        }
+    }
+}
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial(
+    "ReaderUpdate",
+    derive(Debug, Clone, PartialEq),
+    omit(id),
+    optional(email via Undefinable, bio),
+    borrowed,
+    cross_eq,
+    try_rebuild,
+    accessors
+)]
+struct Reader {
+    id: u32,
+    name: String,
+    email: String,
+    bio: String,
+}
+
+#[test]
+fn optional_field_via_custom_wrapper() {
+    let reader = Reader {
+        id: 3,
+        name: "Ada".to_string(),
+        email: "ada@example.com".to_string(),
+        bio: "Mathematician".to_string(),
+    };
+
+    let update = ReaderUpdate::from(reader.clone());
+    assert_eq!(
+        update.email,
+        Undefinable::Value("ada@example.com".to_string())
+    );
+    assert_eq!(update, reader);
+    assert_eq!(update.as_partial_ref().into_owned(), update);
+
+    let null_email = ReaderUpdate {
+        name: "Grace".to_string(),
+        email: Undefinable::Null,
+        bio: None,
+    };
+    assert_eq!(null_email.fold_set_optionals(0, |count, _| count + 1), 0);
+    let rebuilt = null_email.clone().to_reader_or_current(reader.clone());
+    assert_eq!(rebuilt.name, "Grace");
+    assert_eq!(rebuilt.email, "ada@example.com");
+    assert_eq!(
+        null_email
+            .clone()
+            .try_to_reader(ReaderUpdateOmitted { id: 3 }),
+        Err(ReaderUpdateMissingField::Email)
+    );
+
+    let rebuilt = null_email.overlay(update).to_reader(4, None, None);
+    assert_eq!(rebuilt.id, 4);
+    assert_eq!(rebuilt.email, "ada@example.com");
+}
+
+#[test]
+fn schema_describes_partial_fields() {
+    use partial_struct::FieldSchema;
+
+    assert_eq!(
+        PartialUser::schema(),
+        vec![
+            FieldSchema {
+                name: "name",
+                type_name: "String",
+                optional: false
+            },
+            FieldSchema {
+                name: "email",
+                type_name: "String",
+                optional: true
+            },
+        ]
+    );
+    assert_eq!(
+        ReaderUpdate::schema()
+            .iter()
+            .map(|field| field.name)
+            .collect::<Vec<_>>(),
+        ReaderUpdate::included_field_names()
+    );
+}
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial("Invoice", derive(Debug, PartialEq), omit(internal_note), skip_from)]
+struct Order {
+    id: u32,
+    total_cents: u64,
+    internal_note: String,
+}
+
+impl From<Order> for Invoice {
+    fn from(order: Order) -> Self {
+        Invoice {
+            id: order.id,
+            total_cents: order.total_cents + 100,
+        }
+    }
+}
+
+#[test]
+fn skip_from_allows_a_hand_written_from() {
+    let order = Order {
+        id: 5,
+        total_cents: 1_000,
+        internal_note: "rush".to_string(),
+    };
+
+    let invoice = Invoice::from(order.clone());
+    assert_eq!(
+        invoice,
+        Invoice {
+            id: 5,
+            total_cents: 1_100
+        }
+    );
+
+    let (invoice, omitted) = order.clone().into_invoice_with_omitted();
+    assert_eq!(invoice.total_cents, 1_000);
+    assert_eq!(omitted.internal_note, "rush");
+    assert_eq!(invoice.to_order(omitted.internal_note), order);
+}
+
+#[derive(Partial)]
+#[partial(
+    "TaggedIdPatch",
+    derive(Debug),
+    omit(created_at),
+    optional(label),
+    manual_clone
+)]
+struct TaggedId<T> {
+    id: u64,
+    label: String,
+    #[allow(dead_code)]
+    created_at: u64,
+    marker: std::marker::PhantomData<T>,
+}
+
+/// Not `Clone`, so a derived `Clone` on the partial would be unusable with it.
+#[derive(Debug)]
+struct NotClone;
+
+#[test]
+fn manual_clone_skips_bounds_on_phantom_parameters() {
+    let patch: TaggedIdPatch<NotClone> = TaggedIdPatch {
+        id: 1,
+        label: Some("first".to_string()),
+        marker: std::marker::PhantomData,
+    };
+
+    let copy = patch.clone();
+    assert_eq!(copy.id, 1);
+    assert_eq!(copy.label.as_deref(), Some("first"));
+    assert_eq!(patch.to_tagged_id(10, None).label, "first");
+}
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial(
+    "Draft",
+    derive(Debug, PartialEq),
+    omit(id),
+    optional(body),
+    module = "note_partial"
+)]
+struct Note {
+    id: u32,
+    title: String,
+    body: String,
+}
+
+#[test]
+fn module_wraps_generated_items() {
+    let note = Note {
+        id: 2,
+        title: "Groceries".to_string(),
+        body: "milk".to_string(),
+    };
+
+    let (draft, omitted): (note_partial::Draft, note_partial::DraftOmitted) =
+        note.clone().into_draft_with_omitted();
+    assert_eq!(draft.title, "Groceries");
+    assert_eq!(draft.to_note(omitted.id, None), note);
+}
+
+#[derive(Partial, Debug, PartialEq)]
+#[partial(derive(Debug), omit(id), optional(title, tags, cover))]
+struct Album {
+    id: u32,
+    title: Box<str>,
+    tags: std::sync::Arc<[String]>,
+    cover: Box<Option<u32>>,
+}
+
+#[test]
+fn optional_box_fallbacks_take_the_owned_value() {
+    let partial = PartialAlbum {
+        title: None,
+        tags: None,
+        cover: Some(Box::new(Some(7))),
+    };
+
+    let tags: std::sync::Arc<[String]> = vec!["jazz".to_string()].into();
+    let album = partial.to_album(1, Some("Blue".to_string()), Some(tags.clone()), None);
+    assert_eq!(
+        album,
+        Album {
+            id: 1,
+            title: "Blue".into(),
+            tags,
+            cover: Box::new(Some(7)),
+        }
+    );
+}
+
+#[test]
+fn source_struct_names_the_original() {
+    assert_eq!(PartialUser::SOURCE_STRUCT, "User");
+    assert_eq!(note_partial::Draft::SOURCE_STRUCT, "Note");
+}
+
+#[test]
+fn changed_lists_set_optionals_with_display_values() {
+    let update = ReaderUpdate {
+        name: "Ada".to_string(),
+        email: Undefinable::Value("ada@example.com".to_string()),
+        bio: None,
+    };
+    assert_eq!(
+        update.changed(),
+        vec![("email", "ada@example.com".to_string())]
+    );
+
+    let set_clause = update
+        .changed()
+        .iter()
+        .map(|(column, _)| format!("{} = ?", column))
+        .collect::<Vec<_>>()
+        .join(", ");
+    assert_eq!(set_clause, "email = ?");
+}
+
+#[derive(Partial)]
+#[partial(
+    "UserRowPartial",
+    derive(Debug, PartialEq),
+    omit(password_hash),
+    optional(nickname)
+)]
+struct UserRow {
+    id: u32,
+    #[allow(dead_code)]
+    password_hash: String,
+    nickname: String,
+}
+
+#[derive(Partial)]
+#[partial(
+    "UserDtoPartial",
+    derive(Debug, PartialEq),
+    omit(links),
+    optional(nickname),
+    structural_from(UserRowPartial)
+)]
+struct UserDto {
+    id: u32,
+    nickname: String,
+    #[allow(dead_code)]
+    links: Vec<String>,
+}
+
+#[test]
+fn structural_from_converts_between_same_shaped_partials() {
+    let row = UserRowPartial {
+        id: 4,
+        nickname: Some("ada".to_string()),
+    };
+
+    let dto = UserDtoPartial::from(row);
+    assert_eq!(
+        dto,
+        UserDtoPartial {
+            id: 4,
+            nickname: Some("ada".to_string()),
+        }
+    );
+    assert_eq!(dto.to_user_dto(Vec::new(), None).nickname, "ada");
+}
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial(derive(Debug, PartialEq), omit(id), optional_empty(tags), cross_eq)]
+struct Bookmark {
+    id: u32,
+    url: String,
+    tags: Vec<String>,
+}
+
+#[test]
+fn optional_empty_treats_empty_collections_as_absent() {
+    let bookmark = Bookmark {
+        id: 1,
+        url: "https://example.com".to_string(),
+        tags: Vec::new(),
+    };
+
+    let partial = PartialBookmark::from(bookmark.clone());
+    assert_eq!(partial.tags, None);
+    assert_eq!(partial, bookmark);
+    assert_eq!(partial.to_bookmark(1), bookmark);
+
+    let tagged = PartialBookmark::from(Bookmark {
+        tags: vec!["rust".to_string()],
+        ..bookmark
+    });
+    assert_eq!(tagged.tags, Some(vec!["rust".to_string()]));
+}
+
+#[test]
+fn for_each_field_visits_kept_fields_with_debug_values() {
+    let partial = PartialBookmark {
+        url: "https://example.com".to_string(),
+        tags: Some(vec!["rust".to_string()]),
+    };
+
+    let mut visited = Vec::new();
+    partial.for_each_field(|name, value| visited.push((name, format!("{:?}", value))));
+    assert_eq!(
+        visited,
+        [
+            ("url", "\"https://example.com\"".to_string()),
+            ("tags", "Some([\"rust\"])".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn from_parts_takes_fields_as_stored() {
+    let partial = PartialBookmark::from_parts(
+        "https://example.com".to_string(),
+        Some(vec!["rust".to_string()]),
+    );
+    assert_eq!(
+        partial,
+        PartialBookmark {
+            url: "https://example.com".to_string(),
+            tags: Some(vec!["rust".to_string()]),
+        }
+    );
+}
+
+#[test]
+fn present_and_absent_optionals_split_the_optional_fields() {
+    let contact = ContactCard {
+        name: "Ada".to_string(),
+        email: None,
+        phone: Some("555-0100".to_string()),
+    };
+    assert_eq!(contact.present_optionals(), ["phone"]);
+    assert_eq!(contact.absent_optionals(), ["email"]);
+
+    let patch = PartialBookmark {
+        url: "https://example.com".to_string(),
+        tags: Some(Vec::new()),
+    };
+    assert_eq!(patch.present_optionals(), ["tags"]);
+    assert!(patch.absent_optionals().is_empty());
+}
+
+#[test]
+fn accessors_borrow_optional_fields() {
+    let mut update = ReaderUpdate {
+        name: "Ada".to_string(),
+        email: Undefinable::Value("ada@example.com".to_string()),
+        bio: None,
+    };
+
+    assert_eq!(update.email().map(String::as_str), Some("ada@example.com"));
+    assert_eq!(update.bio(), None);
+
+    update.email_mut().unwrap().push_str(".org");
+    assert_eq!(
+        update.email,
+        Undefinable::Value("ada@example.com.org".to_string())
+    );
+    assert!(update.bio_mut().is_none());
+}
+
+macro_rules! define_record {
+    ($name:ident { $($field:ident: $ty:ty),* $(,)? }) => {
+        #[derive(Partial, Debug, PartialEq)]
+        #[partial(derive(Debug, PartialEq), omit(id), optional(label))]
+        struct $name {
+            id: u32,
+            label: String,
+            $($field: $ty,)*
+        }
+    };
+}
+
+define_record!(Foo { weight: u8 });
+
+#[test]
+fn derive_on_struct_from_macro_rules() {
+    let partial = PartialFoo {
+        label: None,
+        weight: 3,
+    };
+
+    let foo = partial.to_foo(1, Some("bar".to_string()));
+    assert_eq!(
+        foo,
+        Foo {
+            id: 1,
+            label: "bar".to_string(),
+            weight: 3,
+        }
+    );
+    let (partial, omitted) = foo.into_partial_foo_with_omitted();
+    assert_eq!(partial.label.as_deref(), Some("bar"));
+    assert_eq!(omitted.id, 1);
+}
+
+#[test]
+fn empty_patch_overlays_a_single_field() {
+    const NO_CHANGES: SettingsPatch = SettingsPatch::empty();
+    assert_eq!(NO_CHANGES, SettingsPatch::default());
+
+    let patch = SettingsPatch::empty().overlay(SettingsPatch {
+        font_size: Some(16),
+        ..SettingsPatch::empty()
+    });
+    assert_eq!(patch.font_size, Some(16));
+    assert_eq!(patch.theme, None);
+    assert_eq!(patch.notifications, None);
+}
+
+#[derive(Partial)]
+#[partial(
+    "Coordinates",
+    derive(Debug, std::clone::Clone, serde::Serialize, serde::Deserialize),
+    omit(label)
+)]
+struct Waypoint {
+    #[allow(dead_code)]
+    label: String,
+    lat: i32,
+    lon: i32,
+}
+
+#[test]
+fn derive_accepts_path_qualified_traits() {
+    let coordinates = Coordinates { lat: 52, lon: 13 };
+    let json = serde_json::to_string(&coordinates.clone()).unwrap();
+    assert_eq!(json, r#"{"lat":52,"lon":13}"#);
+
+    let parsed: Coordinates = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.to_waypoint("Berlin".to_string()).lat, 52);
+}
+
+#[derive(Partial, Debug)]
+#[partial(derive(Debug, PartialEq), omit(password), optional(bio), from_ref)]
+struct Login {
+    username: String,
+    password: String,
+    bio: String,
+}
+
+#[test]
+fn from_ref_projects_without_taking_ownership() {
+    let login = Login {
+        username: "ada".to_string(),
+        password: "hunter2".to_string(),
+        bio: "".to_string(),
+    };
+
+    let partial = PartialLogin::from(&login);
+    assert_eq!(
+        partial,
+        PartialLogin {
+            username: "ada".to_string(),
+            bio: Some("".to_string()),
+        }
+    );
+    assert_eq!(login.password, "hunter2");
+    let also: PartialLogin = (&login).into();
+    assert_eq!(also, partial);
+}
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial("ShipmentTuple", omit(id, carrier), omitted_as(tuple))]
+struct Shipment {
+    id: u32,
+    weight: u16,
+    carrier: String,
+}
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial("ParcelStruct", omit(id, carrier), omitted_as(struct))]
+struct Parcel {
+    id: u32,
+    weight: u16,
+    carrier: String,
+}
+
+#[test]
+fn omitted_as_tuple_or_struct() {
+    let shipment = Shipment {
+        id: 8,
+        weight: 20,
+        carrier: "post".to_string(),
+    };
+
+    let (partial, omitted): (ShipmentTuple, (u32, String)) =
+        ShipmentTuple::from_shipment_with_omitted(shipment.clone());
+    assert_eq!(omitted, (8, "post".to_string()));
+    assert_eq!(partial.take_omitted(omitted), shipment);
+
+    let parcel = Parcel {
+        id: 8,
+        weight: 20,
+        carrier: "post".to_string(),
+    };
+    let (partial, omitted): (ParcelStruct, ParcelStructOmitted) =
+        parcel.clone().into_parcel_struct_with_omitted();
+    assert_eq!((omitted.id, omitted.carrier.as_str()), (8, "post"));
+    assert_eq!(partial.take_omitted(omitted), parcel);
+}
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial(derive(Debug, Clone, PartialEq), omit(children))]
+struct Node {
+    value: i32,
+    next: Option<Box<Node>>,
+    children: Vec<Node>,
+}
+
+#[test]
+fn self_referential_fields_rebuild() {
+    let leaf = Node {
+        value: 2,
+        next: None,
+        children: Vec::new(),
+    };
+    let root = Node {
+        value: 1,
+        next: Some(Box::new(leaf.clone())),
+        children: vec![leaf.clone()],
+    };
+
+    let (partial, omitted) = root.clone().into_partial_node_with_omitted();
+    assert_eq!(partial.next.as_deref(), Some(&leaf));
+    assert_eq!(partial.to_node_cloned(omitted.children.clone()), root);
+    assert_eq!(partial.to_node(omitted.children), root);
+}
+
+#[allow(clippy::duplicated_attributes)]
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial(
+    "CustomerDto",
+    derive(Debug, PartialEq),
+    omit(password),
+    optional(email),
+    rename(name = "full_name", email = "contact"),
+    cross_eq
+)]
+#[partial("CustomerRow", derive(Debug, PartialEq), rename(name = "display_name"))]
+struct Customer {
+    name: String,
+    email: String,
+    password: String,
+}
+
+#[test]
+fn rename_is_scoped_to_one_partial() {
+    let customer = Customer {
+        name: "Ada Lovelace".to_string(),
+        email: "ada@example.com".to_string(),
+        password: "secret".to_string(),
+    };
+
+    let dto = CustomerDto::from(customer.clone());
+    assert_eq!(dto.full_name, "Ada Lovelace");
+    assert_eq!(dto.contact.as_deref(), Some("ada@example.com"));
+    assert_eq!(dto, customer);
+    assert_eq!(
+        CustomerDto::included_field_names(),
+        ["full_name", "contact"]
+    );
+
+    let row = CustomerRow::from(customer.clone());
+    assert_eq!(row.display_name, "Ada Lovelace");
+    assert_eq!(row.email, "ada@example.com");
+
+    let mut updated = customer.clone();
+    CustomerDto {
+        full_name: "Ada King".to_string(),
+        contact: None,
+    }
+    .merge_into(&mut updated);
+    assert_eq!(updated.name, "Ada King");
+    assert_eq!(updated.email, "ada@example.com");
+    assert_eq!(dto.to_customer("secret".to_string(), None), customer);
+}
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial(
+    "PatronCard",
+    derive(Debug, PartialEq),
+    omit(id),
+    optional(email),
+    constructor
+)]
+struct Patron {
+    id: u32,
+    name: String,
+    email: String,
+    level: u8,
+}
+
+#[test]
+fn constructor_takes_fields_in_declaration_order() {
+    let card = PatronCard::new("Ada".to_string(), Some("ada@example.com".to_string()), 3);
+    assert_eq!(
+        card,
+        PatronCard {
+            name: "Ada".to_string(),
+            email: Some("ada@example.com".to_string()),
+            level: 3,
+        }
+    );
+    assert_eq!(PatronCard::new("Bob".to_string(), None, 1).email, None);
+}
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial("SwatchLabel", derive(Debug, PartialEq), omit_type(u8))]
+struct Swatch {
+    name: String,
+    red: u8,
+    green: u8,
+    blue: u8,
+    opacity: f32,
+}
+
+#[test]
+fn omit_type_drops_every_field_of_that_type() {
+    let swatch = Swatch {
+        name: "teal".to_string(),
+        red: 0,
+        green: 128,
+        blue: 128,
+        opacity: 0.5,
+    };
+    let label = SwatchLabel::from(swatch.clone());
+    assert_eq!(
+        label,
+        SwatchLabel {
+            name: "teal".to_string(),
+            opacity: 0.5,
+        }
+    );
+    assert_eq!(SwatchLabel::omitted_field_names(), ["red", "green", "blue"]);
+    assert_eq!(label.to_swatch(0, 128, 128), swatch);
+}
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial(
+    "BadgeView",
+    derive(Debug, Clone, PartialEq),
+    omit(serial),
+    optional(holder),
+    frozen
+)]
+struct Badge {
+    serial: u64,
+    title: String,
+    holder: String,
+}
+
+#[test]
+fn frozen_partial_exposes_only_getters() {
+    let badge = Badge {
+        serial: 7,
+        title: "Visitor".to_string(),
+        holder: "Ada".to_string(),
+    };
+    let frozen = BadgeView::from(badge.clone()).into_frozen();
+    assert_eq!(frozen.title(), "Visitor");
+    assert_eq!(frozen.holder().map(String::as_str), Some("Ada"));
+    assert_eq!(frozen.clone().into_inner().to_badge(7, None), badge);
+
+    let shared = BadgeView::from(badge).freeze();
+    assert_eq!(shared.title, "Visitor");
+    assert_eq!(std::sync::Arc::strong_count(&shared), 1);
+}
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial(
+    "ServiceLogin",
+    derive(Debug),
+    omit(user, password, token),
+    redact(password, token)
+)]
+struct ServiceAccount {
+    host: String,
+    user: String,
+    password: String,
+    token: String,
+}
+
+#[test]
+fn redact_hides_secrets_in_omitted_debug() {
+    let account = ServiceAccount {
+        host: "db.internal".to_string(),
+        user: "admin".to_string(),
+        password: "hunter2".to_string(),
+        token: "abc123".to_string(),
+    };
+    let (_, omitted) = ServiceLogin::from_service_account_with_omitted(account);
+    let printed = format!("{:?}", omitted);
+    assert_eq!(
+        printed,
+        r#"ServiceLoginOmitted { user: "admin", password: <redacted>, token: <redacted> }"#
+    );
+    assert!(!printed.contains("hunter2"));
+}
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial("CatalogEntry", omit(internal_code), optional(price_cents), field_enum)]
+struct CatalogItem {
+    internal_code: u32,
+    display_name: String,
+    price_cents: u64,
+}
+
+#[test]
+fn field_enum_names_the_kept_fields() {
+    let column = |field: CatalogEntryField| match field {
+        CatalogEntryField::DisplayName => "display_name TEXT",
+        CatalogEntryField::PriceCents => "price_cents INTEGER",
+    };
+    assert_eq!(column(CatalogEntryField::PriceCents), "price_cents INTEGER");
+    assert_eq!(CatalogEntryField::DisplayName.name(), "display_name");
+    assert_eq!(
+        [
+            CatalogEntryField::DisplayName,
+            CatalogEntryField::PriceCents
+        ]
+        .map(|field| field.name()),
+        CatalogEntry::included_field_names()
+    );
+}
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial(
+    "DeploymentSummary",
+    omit(region, replicas),
+    omitted_derive(Debug, Clone, PartialEq)
+)]
+struct Deployment {
+    name: String,
+    region: String,
+    replicas: u16,
+}
+
+#[test]
+fn omitted_derive_applies_to_the_omitted_struct() {
+    let deployment = Deployment {
+        name: "api".to_string(),
+        region: "eu-west".to_string(),
+        replicas: 3,
+    };
+    let (summary, omitted) = DeploymentSummary::from_deployment_with_omitted(deployment.clone());
+    let copy = omitted.clone();
+    assert_eq!(copy, omitted);
+    assert_eq!(copy.region, "eu-west");
+    assert_eq!(
+        summary.to_deployment(omitted.region, omitted.replicas),
+        deployment
+    );
+}
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial("MarkerOnly", derive(Debug, PartialEq), omit(id, label), allow_empty)]
+struct Marker {
+    id: u32,
+    label: String,
+}
+
+#[test]
+fn allow_empty_accepts_a_partial_without_fields() {
+    let marker = Marker {
+        id: 1,
+        label: "start".to_string(),
+    };
+    let empty = MarkerOnly::from(marker.clone());
+    assert_eq!(empty, MarkerOnly {});
+    assert_eq!(empty.to_marker(1, "start".to_string()), marker);
+}
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial(
+    "JobDraft",
+    omit(id, retries, tags),
+    optional(timeout_secs = 30),
+    default(id = "0", retries = "3", tags = "vec![\"new\".to_string()]")
+)]
+struct Job {
+    id: u64,
+    user: String,
+    retries: u8,
+    timeout_secs: u32,
+    tags: Vec<String>,
+}
+
+#[test]
+fn defaulted_rebuild_fills_every_omitted_field() {
+    let draft = JobDraft {
+        user: "ada".to_string(),
+        timeout_secs: None,
+    };
+    assert_eq!(
+        draft.to_job_defaulted(),
+        Job {
+            id: 0,
+            user: "ada".to_string(),
+            retries: 3,
+            timeout_secs: 30,
+            tags: vec!["new".to_string()],
+        }
+    );
+}
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial("ProfileEdit", derive(Debug, PartialEq), omit(id), optional(bio, age))]
+struct Biography {
+    id: u32,
+    bio: Option<String>,
+    age: u16,
+}
+
+#[test]
+fn optional_keeps_an_existing_option_field_as_is() {
+    let biography = Biography {
+        id: 4,
+        bio: None,
+        age: 36,
+    };
+    let edit = ProfileEdit::from(biography.clone());
+    let bio: &Option<String> = &edit.bio;
+    assert_eq!(bio, &None);
+    assert_eq!(edit.age, Some(36));
+    assert_eq!(edit.to_biography(4, None), biography);
+
+    let with_bio = ProfileEdit {
+        bio: Some("Mathematician".to_string()),
+        age: None,
+    };
+    assert_eq!(
+        with_bio.to_biography(4, Some(36)).bio.as_deref(),
+        Some("Mathematician")
+    );
+}
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial(
+    "CommentBody",
+    derive(Debug, PartialEq),
+    omit(id, posted_at),
+    optional(edited)
+)]
+struct Comment {
+    id: u64,
+    posted_at: u64,
+    text: String,
+    edited: bool,
+}
+
+#[test]
+fn omitted_struct_rebuilds_the_full_struct() {
+    let comment = Comment {
+        id: 9,
+        posted_at: 1_700_000_000,
+        text: "First!".to_string(),
+        edited: false,
+    };
+    let (body, omitted) = CommentBody::from_comment_with_omitted(comment.clone());
+
+    let stamped = CommentBodyOmitted {
+        id: omitted.id,
+        posted_at: omitted.posted_at,
+    };
+    assert_eq!(stamped.into_comment(body, None), comment);
+
+    let draft = CommentBody {
+        text: "Edited".to_string(),
+        edited: None,
+    };
+    let rebuilt = omitted.into_comment(draft, Some(true));
+    assert_eq!(
+        (rebuilt.id, rebuilt.text.as_str(), rebuilt.edited),
+        (9, "Edited", true)
+    );
+}
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial("UploadForm", omit(id), optional(partial))]
+struct Upload {
+    id: u64,
+    name: String,
+    partial: bool,
+}
+
+#[test]
+fn omitted_struct_rebuilds_with_an_optional_field_named_partial() {
+    let form = UploadForm {
+        name: "scan.pdf".to_string(),
+        partial: None,
+    };
+    let upload = UploadFormOmitted { id: 2 }.into_upload(form, Some(true));
+    assert_eq!(
+        (upload.id, upload.name.as_str(), upload.partial),
+        (2, "scan.pdf", true)
+    );
+}
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial("PackingLine", omit(id), optional(omitted))]
+struct PackingItem {
+    id: u32,
+    sku: String,
+    omitted: bool,
+}
+
+#[test]
+fn take_omitted_with_an_optional_field_named_omitted() {
+    let line = PackingLine {
+        sku: "A-7".to_string(),
+        omitted: None,
+    };
+    let item = line.take_omitted(PackingLineOmitted { id: 4 }, Some(false));
+    assert_eq!(
+        (item.id, item.sku.as_str(), item.omitted),
+        (4, "A-7", false)
+    );
+}
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial(
+    "ThemeColors",
+    derive(Debug, PartialEq),
+    omit(name),
+    cfg_derive(feature = "json", Eq, Hash),
+    cfg_derive(not(feature = "json"), Clone, Copy)
+)]
+struct Theme {
+    name: String,
+    foreground: u32,
+    background: u32,
+}
+
+#[test]
+fn cfg_derive_applies_only_when_the_predicate_holds() {
+    let colors = ThemeColors {
+        foreground: 0xffffff,
+        background: 0x000000,
+    };
+    #[cfg(feature = "json")]
+    {
+        fn assert_hash<T: std::hash::Hash + Eq>(_: &T) {}
+        assert_hash(&colors);
+    }
+    #[cfg(not(feature = "json"))]
+    {
+        let copy = colors;
+        assert_eq!(copy, colors);
+    }
+}
+
+#[derive(Partial, Debug, Clone, Copy, PartialEq)]
+#[repr(C)]
+#[partial("PacketHeader", derive(Debug, PartialEq), omit(checksum), keep_repr)]
+struct Packet {
+    kind: u8,
+    len: u16,
+    checksum: u32,
+}
+
+#[test]
+fn repr_c_partial_omitting_trailing_fields_is_a_layout_prefix() {
+    assert_eq!(
+        std::mem::offset_of!(PacketHeader, len),
+        std::mem::offset_of!(Packet, len)
+    );
+    let header = PacketHeader::from(Packet {
+        kind: 1,
+        len: 64,
+        checksum: 0xdead_beef,
+    });
+    assert_eq!(header, PacketHeader { kind: 1, len: 64 });
+}
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial("SettingPatch", derive(Debug), omit(key), optional(value = T::default()), where_bound("T: Default"))]
+struct Setting<T> {
+    key: String,
+    value: T,
+}
+
+#[test]
+fn where_bound_covers_bounds_the_macro_cannot_infer() {
+    let patch: SettingPatch<u32> = SettingPatch { value: None };
+    assert_eq!(
+        patch.to_setting_cloned("retries".to_string()),
+        Setting {
+            key: "retries".to_string(),
+            value: 0,
+        }
+    );
+    let patch = SettingPatch { value: Some(5u32) };
+    assert_eq!(patch.to_setting("retries".to_string()).value, 5);
+}
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial(
+    "PostDraft",
+    derive(Debug, PartialEq),
+    omit(id),
+    optional(summary),
+    rename(title = "headline"),
+    view
+)]
+struct BlogPost {
+    id: u64,
+    title: String,
+    summary: String,
+    body: String,
+}
+
+#[test]
+fn view_borrows_the_partial_and_omitted_fields() {
+    let draft = PostDraft {
+        headline: "Partial structs".to_string(),
+        summary: None,
+        body: "...".to_string(),
+    };
+    let id = 12;
+    let view = draft.to_blog_post_ref(&id);
+    assert_eq!(*view.id, 12);
+    assert_eq!(view.title, "Partial structs");
+    assert_eq!(view.summary, None);
+    assert!(std::ptr::eq(view.body, &draft.body));
+
+    // The partial is only borrowed, so it is still usable.
+    assert_eq!(
+        draft.to_blog_post(id, Some(String::new())).title,
+        "Partial structs"
+    );
+}
+
+#[derive(Partial, Debug, PartialEq)]
+#[partial("CourierDraft", omit(id, attempts))]
+struct Courier {
+    #[partial_default(0)]
+    id: u64,
+    address: String,
+    #[partial_default(Vec::new())]
+    attempts: Vec<u8>,
+}
+
+#[test]
+fn field_level_default_rebuilds_without_arguments() {
+    let draft = CourierDraft {
+        address: "1 Main St".to_string(),
+    };
+    assert_eq!(
+        draft.to_courier_defaulted(),
+        Courier {
+            id: 0,
+            address: "1 Main St".to_string(),
+            attempts: Vec::new(),
+        }
+    );
+}
+
+#[derive(Partial, Debug, PartialEq)]
+#[partial(
+    "ApplicantForm",
+    omit(id),
+    optional(email, phone, city),
+    freeze_optional
+)]
+struct Applicant {
+    id: u32,
+    name: String,
+    email: String,
+    phone: String,
+    city: String,
+}
+
+#[test]
+fn freeze_optional_reports_every_missing_field() {
+    let form = ApplicantForm {
+        name: "Ada".to_string(),
+        email: None,
+        phone: Some("555-0100".to_string()),
+        city: None,
+    };
+    let err = form.complete(3).unwrap_err();
+    assert_eq!(err.fields, ["email", "city"]);
+    assert_eq!(err.to_string(), "missing optional fields: `email`, `city`");
+
+    let form = ApplicantForm {
+        name: "Ada".to_string(),
+        email: Some("ada@example.com".to_string()),
+        phone: Some("555-0100".to_string()),
+        city: Some("London".to_string()),
+    };
+    assert_eq!(
+        form.complete(3),
+        Ok(Applicant {
+            id: 3,
+            name: "Ada".to_string(),
+            email: "ada@example.com".to_string(),
+            phone: "555-0100".to_string(),
+            city: "London".to_string(),
+        })
+    );
+}
+
+#[derive(Partial, Debug, PartialEq)]
+#[partial("ExportForm", omit(fields), optional(format), freeze_optional)]
+struct Export {
+    fields: Vec<String>,
+    format: String,
+}
+
+#[test]
+fn freeze_optional_with_an_omitted_field_named_fields() {
+    let form = ExportForm {
+        format: Some("csv".to_string()),
+    };
+    let export = form.complete(vec!["id".to_string()]).unwrap();
+    assert_eq!(
+        (export.fields, export.format.as_str()),
+        (vec!["id".to_string()], "csv")
+    );
+
+    let err = ExportForm { format: None }
+        .complete(Vec::new())
+        .unwrap_err();
+    assert_eq!(err.fields, ["format"]);
+}
+
+#[allow(clippy::duplicated_attributes)]
+#[derive(Partial, Debug, PartialEq)]
+#[partial_common(omit(id), optional(updated_at))]
+#[partial("LedgerCreate", derive(Debug, PartialEq))]
+#[partial("LedgerRename", derive(Debug, PartialEq), omit(balance))]
+struct Ledger {
+    id: u32,
+    name: String,
+    balance: i64,
+    updated_at: u64,
+}
+
+#[test]
+fn partial_common_entries_apply_to_every_partial() {
+    assert_eq!(LedgerCreate::omitted_field_names(), ["id"]);
+    assert_eq!(LedgerRename::omitted_field_names(), ["id", "balance"]);
+
+    let create = LedgerCreate {
+        name: "Cash".to_string(),
+        balance: 100,
+        updated_at: None,
+    };
+    assert_eq!(
+        create.to_ledger(1, Some(7)),
+        Ledger {
+            id: 1,
+            name: "Cash".to_string(),
+            balance: 100,
+            updated_at: 7,
+        }
+    );
+
+    let rename = LedgerRename {
+        name: "Savings".to_string(),
+        updated_at: Some(8),
+    };
+    assert_eq!(rename.to_ledger(2, 50, None).updated_at, 8);
+}
+
+#[derive(Partial, Debug, PartialEq)]
+#[partial("RecipeCard", derive(Debug, PartialEq), omit(steps), from_mode(clone))]
+struct Recipe {
+    title: String,
+    servings: u8,
+    steps: Vec<String>,
+}
+
+#[test]
+fn from_mode_clone_converts_from_a_borrow() {
+    let recipe = Recipe {
+        title: "Soup".to_string(),
+        servings: 4,
+        steps: vec!["Boil water".to_string()],
+    };
+
+    let card = RecipeCard::from(&recipe);
+    assert_eq!(
+        card,
+        RecipeCard {
+            title: "Soup".to_string(),
+            servings: 4,
+        }
+    );
+    // The full struct was only borrowed.
+    assert_eq!(card.to_recipe(recipe.steps.clone()), recipe);
+}
+
+/// Records whether the wrapped value was replaced after wrapping.
+#[derive(Debug, Clone, PartialEq)]
+struct Tracked<T> {
+    value: T,
+    modified: bool,
+}
+
+impl<T> Tracked<T> {
+    fn set(&mut self, value: T) {
+        self.value = value;
+        self.modified = true;
+    }
+}
+
+impl<T> From<T> for Tracked<T> {
+    fn from(value: T) -> Self {
+        Tracked {
+            value,
+            modified: false,
+        }
+    }
+}
+
+impl<T> partial_struct::FieldWrapper for Tracked<T> {
+    type Inner = T;
+
+    fn into_inner(self) -> T {
+        self.value
+    }
+
+    fn inner(&self) -> &T {
+        &self.value
+    }
+}
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial(
+    "GadgetEdit",
+    derive(Debug),
+    omit(id),
+    optional(note),
+    wrap_all(Tracked)
+)]
+struct Gadget {
+    id: u32,
+    name: String,
+    stock: u16,
+    note: String,
+}
+
+#[test]
+fn wrap_all_tracks_included_fields() {
+    let gadget = Gadget {
+        id: 1,
+        name: "Widget".to_string(),
+        stock: 5,
+        note: "fragile".to_string(),
+    };
+
+    let mut edit = GadgetEdit::from(gadget.clone());
+    assert_eq!(edit.name, Tracked::from("Widget".to_string()));
+    assert_eq!(edit.note.as_deref(), Some("fragile"));
+    edit.stock.set(4);
+    assert!(edit.stock.modified && !edit.name.modified);
+    assert_eq!(edit.diff(&gadget), ["stock"]);
+
+    let rebuilt = edit.to_gadget(1, None);
+    assert_eq!(rebuilt, Gadget { stock: 4, ..gadget });
+}
+
+partial_struct::partial_for! {
+    #[derive(Debug, Clone, PartialEq)]
+    #[partial("ShelfDraft", derive(Debug, PartialEq), omit(id), optional(label))]
+    struct Shelf {
+        #[partial_default(0)]
+        id: u32,
+        #[partial_group(shelf_draft)]
+        capacity: u16,
+        label: String,
+    }
+}
+
+#[test]
+fn partial_for_declares_the_struct_and_its_partials() {
+    let shelf = Shelf {
+        id: 3,
+        capacity: 40,
+        label: "B2".to_string(),
+    };
+
+    let draft = ShelfDraft::from(shelf.clone());
+    assert_eq!(
+        draft,
+        ShelfDraft {
+            capacity: 40,
+            label: Some("B2".to_string()),
+        }
+    );
+    assert_eq!(draft.to_shelf(3, None), shelf);
+}
+
+#[allow(clippy::duplicated_attributes)]
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial(
+    "MailboxForm",
+    omit(id),
+    optional(address = "unknown@example.com", quota = 1024)
+)]
+#[partial("MailboxSignup", omit(id, quota), optional(address = "guest"))]
+struct Mailbox {
+    id: u32,
+    address: String,
+    quota: u64,
+}
+
+#[test]
+fn none_optional_rebuilds_to_its_configured_default() {
+    let form = MailboxForm {
+        address: None,
+        quota: None,
+    };
+    assert_eq!(
+        form.to_mailbox(1),
+        Mailbox {
+            id: 1,
+            address: "unknown@example.com".to_string(),
+            quota: 1024,
+        }
+    );
+
+    let form = MailboxForm {
+        address: Some("ops@example.com".to_string()),
+        quota: None,
+    };
+    assert_eq!(form.to_mailbox(2).address, "ops@example.com");
+
+    let signup = MailboxSignup { address: None };
+    assert_eq!(signup.to_mailbox(3, 512).address, "guest");
+}
+
+#[allow(clippy::duplicated_attributes)]
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial(
+    "EstimateDraft",
+    derive(Debug, PartialEq),
+    omit(number),
+    optional(memo)
+)]
+#[partial("EstimateSummary", derive(Debug, PartialEq), omit(memo, lines))]
+struct Estimate {
+    number: u32,
+    customer: String,
+    lines: Vec<u64>,
+    memo: String,
+}
+
+#[test]
+fn partials_convert_into_each_other_through_the_full_struct() {
+    let draft = EstimateDraft {
+        customer: "Acme".to_string(),
+        lines: vec![120, 80],
+        memo: None,
+    };
+    let summary = draft.into_estimate_summary(7, Some("net 30".to_string()));
+    assert_eq!(
+        summary,
+        EstimateSummary {
+            number: 7,
+            customer: "Acme".to_string(),
+        }
+    );
+
+    let draft = summary.into_estimate_draft(vec![50], "paid".to_string());
+    assert_eq!(
+        draft,
+        EstimateDraft {
+            customer: "Acme".to_string(),
+            lines: vec![50],
+            memo: Some("paid".to_string()),
+        }
+    );
+}
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial(
+    "ApiKeyDraft",
+    derive(Debug),
+    omit(id, token, ttl),
+    default(ttl = "3600")
+)]
+struct ApiKey {
+    id: u32,
+    token: String,
+    ttl: u64,
+    user: String,
+}
+
+#[test]
+fn omitted_defaults_fill_the_omitted_struct() {
+    let omitted = ApiKeyDraft::omitted_defaults();
+    assert_eq!(
+        (omitted.id, omitted.token.as_str(), omitted.ttl),
+        (0, "", 3600)
+    );
+
+    let draft = ApiKeyDraft {
+        user: "ana".to_string(),
+    };
+    assert_eq!(
+        draft.take_omitted(ApiKeyDraft::omitted_defaults()),
+        ApiKey {
+            id: 0,
+            token: String::new(),
+            ttl: 3600,
+            user: "ana".to_string(),
+        }
+    );
+}
+
+#[derive(Partial, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+#[partial(
+    "PreferencesView",
+    derive(serde::Serialize),
+    omit(user_id),
+    forward_struct_attrs(serde)
+)]
+struct Preferences {
+    user_id: u32,
+    dark_mode: bool,
+    font_size: u8,
+}
+
+#[test]
+fn forwarded_struct_attribute_reaches_the_partial_derive() {
+    let view = PreferencesView::from(Preferences {
+        user_id: 1,
+        dark_mode: true,
+        font_size: 14,
+    });
+    assert_eq!(
+        serde_json::to_string(&view).unwrap(),
+        r#"{"darkMode":true,"fontSize":14}"#
+    );
+}
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial(
+    "ReviewBody",
+    derive(Debug, Clone),
+    omit(id, author),
+    default(id = "0", author = "\"anonymous\".to_string()"),
+    into_params
+)]
+struct Review {
+    id: u64,
+    author: String,
+    text: String,
+}
+
+#[test]
+fn into_params_accepts_str_for_string_omitted_fields() {
+    let body = ReviewBody {
+        text: "Nice!".to_string(),
+    };
+    let expected = Review {
+        id: 4,
+        author: "ana".to_string(),
+        text: "Nice!".to_string(),
+    };
+    assert_eq!(body.to_review_cloned(4u32, "ana"), expected);
+    assert_eq!(body.to_review(4u64, "ana".to_string()), expected);
+
+    let defaulted = ReviewBody {
+        text: "Hi".to_string(),
+    }
+    .to_review_defaulted();
+    assert_eq!((defaulted.id, defaulted.author.as_str()), (0, "anonymous"));
+}
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial(
+    "ListingForm",
+    derive(Debug, PartialEq),
+    omit(id),
+    optional(discount),
+    string_map
+)]
+struct Listing {
+    id: u32,
+    title: String,
+    price: u32,
+    discount: u8,
+}
+
+#[test]
+fn partial_round_trips_through_a_string_map() {
+    let form = ListingForm {
+        title: "Lamp".to_string(),
+        price: 40,
+        discount: None,
+    };
+    let map = form.to_string_map();
+    assert_eq!(
+        map,
+        std::collections::HashMap::from([
+            ("title".to_string(), "Lamp".to_string()),
+            ("price".to_string(), "40".to_string()),
+        ])
+    );
+    assert_eq!(ListingForm::from_string_map(&map), Ok(form));
+
+    let mut map = map;
+    map.insert("discount".to_string(), "15".to_string());
+    assert_eq!(
+        ListingForm::from_string_map(&map).unwrap().discount,
+        Some(15)
+    );
+
+    map.insert("price".to_string(), "cheap".to_string());
+    assert_eq!(
+        ListingForm::from_string_map(&map),
+        Err("field `price`: invalid digit found in string".to_string())
+    );
+    map.remove("title");
+    assert_eq!(
+        ListingForm::from_string_map(&map),
+        Err("missing field `title`".to_string())
+    );
+}
+
+#[derive(Partial, Debug, Clone, PartialEq)]
+#[partial(
+    "RouteForm",
+    derive(Debug, PartialEq),
+    omit(id),
+    optional(map),
+    string_map
+)]
+struct Route {
+    id: u32,
+    name: String,
+    map: String,
+}
+
+#[test]
+fn string_map_accepts_a_field_named_map() {
+    let form = RouteForm {
+        name: "Ridge".to_string(),
+        map: Some("ridge.gpx".to_string()),
+    };
+    let map = form.to_string_map();
+    assert_eq!(map.get("map").map(String::as_str), Some("ridge.gpx"));
+    assert_eq!(RouteForm::from_string_map(&map), Ok(form));
+}
+
+mod private_module {
+    use partial_struct::Partial;
+
+    #[derive(Partial, Debug, Clone, PartialEq)]
+    #[partial(
+        derive(Debug, Clone, PartialEq),
+        omit(id),
+        optional(note),
+        cross_eq,
+        borrowed
+    )]
+    struct Ticket {
+        id: u32,
+        title: String,
+        note: String,
+    }
+
+    #[test]
+    fn derive_inside_private_module() {
+        let ticket = Ticket {
+            id: 1,
+            title: "Fix login".to_string(),
+            note: "urgent".to_string(),
+        };
+
+        let partial = PartialTicket::from(ticket.clone());
+        assert_eq!(partial, ticket);
+        assert_eq!(partial.as_partial_ref().into_owned(), partial);
+
+        let (partial, omitted) = ticket.clone().into_partial_ticket_with_omitted();
+        assert_eq!(partial.take_omitted(omitted, None), ticket);
+    }
+}
+
+mod result_alias {
+    use partial_struct::Partial;
+
+    // A crate-wide alias like this must not change the generated signatures.
+    #[allow(dead_code)]
+    type Result<T> = std::result::Result<T, String>;
+
+    #[derive(Partial, Debug, PartialEq)]
+    #[partial(
+        "FreightDraft",
+        derive(Debug, Clone, PartialEq),
+        omit(id),
+        optional(carrier),
+        try_rebuild,
+        freeze_optional,
+        try_complete,
+        string_map
+    )]
+    pub struct Freight {
+        pub id: u32,
+        pub weight: u32,
+        pub carrier: String,
+    }
+
+    impl partial_struct::Validate for Freight {}
+}
+
+#[test]
+fn fallible_methods_ignore_a_result_alias_in_scope() {
+    let draft = result_alias::FreightDraft {
+        weight: 12,
+        carrier: None,
+    };
+    let omitted = result_alias::FreightDraftOmitted { id: 1 };
+    assert_eq!(
+        draft.clone().try_to_freight(omitted).unwrap_err(),
+        result_alias::FreightDraftMissingField::Carrier
+    );
+    assert_eq!(draft.clone().complete(1).unwrap_err().fields, ["carrier"]);
+    let map = draft.to_string_map();
+    assert_eq!(
+        result_alias::FreightDraft::from_string_map(&map).as_ref(),
+        Ok(&draft)
+    );
+    assert_eq!(
+        result_alias::Freight::try_from(draft),
+        Err(result_alias::FreightDraftCompleteError::MissingField(
+            "carrier"
+        ))
+    );
+}
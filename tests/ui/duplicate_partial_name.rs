@@ -0,0 +1,11 @@
+use partial_struct::Partial;
+
+#[derive(Partial)]
+#[partial("UserDraft", omit(id))]
+#[partial("UserDraft", omit(name))]
+struct User {
+    id: u32,
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use partial_struct::Partial;
+
+#[derive(Partial)]
+#[partial(derive(PartialEq, Eq), omit(id))]
+struct Reading {
+    id: u32,
+    value: f64,
+}
+
+fn main() {}
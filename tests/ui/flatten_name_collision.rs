@@ -0,0 +1,15 @@
+use partial_struct::Partial;
+
+struct Address {
+    name: String,
+    city: String,
+}
+
+#[derive(Partial)]
+struct User {
+    name: String,
+    #[partial_flatten(name: String, city: String)]
+    address: Address,
+}
+
+fn main() {}
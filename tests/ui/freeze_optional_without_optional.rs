@@ -0,0 +1,10 @@
+use partial_struct::Partial;
+
+#[derive(Partial)]
+#[partial("UserForm", omit(id), freeze_optional)]
+struct User {
+    id: u32,
+    name: String,
+}
+
+fn main() {}
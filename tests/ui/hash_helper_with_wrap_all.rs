@@ -0,0 +1,10 @@
+use partial_struct::Partial;
+
+#[derive(Partial)]
+#[partial(omit(id), wrap_all(Box), hash_helper)]
+struct User {
+    id: u32,
+    name: String,
+}
+
+fn main() {}
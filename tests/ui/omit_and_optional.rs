@@ -0,0 +1,11 @@
+use partial_struct::Partial;
+
+#[derive(Partial)]
+#[partial(omit(id, email), optional(email))]
+struct User {
+    id: u32,
+    name: String,
+    email: String,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use partial_struct::Partial;
+
+#[derive(Partial)]
+#[partial("Nothing", omit(id, name))]
+struct User {
+    id: u32,
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use partial_struct::Partial;
+
+#[derive(Partial)]
+#[partial(omit(token), redact(token, password))]
+struct Login {
+    user: String,
+    password: String,
+    token: String,
+}
+
+fn main() {}
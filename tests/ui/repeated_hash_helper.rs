@@ -0,0 +1,12 @@
+use partial_struct::Partial;
+
+#[derive(Partial)]
+#[partial("UserName", omit(id, email), hash_helper)]
+#[partial("UserEmail", omit(id, name), hash_helper)]
+struct User {
+    id: u32,
+    name: String,
+    email: String,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use partial_struct::Partial;
+
+#[derive(Partial)]
+#[partial(omit(id), rebuild_fn = "into_user", rebuild_fn = "to_full")]
+struct User {
+    id: u32,
+    name: String,
+}
+
+fn main() {}
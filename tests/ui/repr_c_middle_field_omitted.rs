@@ -0,0 +1,12 @@
+use partial_struct::Partial;
+
+#[derive(Partial)]
+#[repr(C)]
+#[partial(omit(b), keep_repr)]
+struct Triple {
+    a: u32,
+    b: u32,
+    c: u32,
+}
+
+fn main() {}
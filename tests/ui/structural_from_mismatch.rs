@@ -0,0 +1,15 @@
+use partial_struct::Partial;
+
+pub struct Source {
+    pub id: u32,
+    pub extra: String,
+}
+
+#[derive(Partial)]
+#[partial(omit(secret), structural_from(Source))]
+struct User {
+    id: u32,
+    secret: String,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use partial_struct::Partial;
+
+#[derive(Partial)]
+#[partial(omit(emial))]
+struct User {
+    id: u32,
+    email: String,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use partial_struct::Partial;
+
+#[derive(Partial)]
+#[partial("Creation", omit(id))]
+struct User {
+    id: u32,
+    #[partial_group(creaton)]
+    name: String,
+}
+
+fn main() {}
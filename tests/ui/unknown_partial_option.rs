@@ -0,0 +1,10 @@
+use partial_struct::Partial;
+
+#[derive(Partial)]
+#[partial("UserDraft", omit(id), optionl(name))]
+struct User {
+    id: u32,
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use partial_struct::Partial;
+
+#[derive(Partial)]
+#[partial(omit(r#match))]
+struct Token {
+    id: u32,
+    r#type: String,
+}
+
+fn main() {}
@@ -0,0 +1,34 @@
+#![cfg(feature = "zeroize")]
+
+use partial_struct::Partial;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+#[derive(Partial, Debug)]
+#[partial(derive(Debug), omit(password, token), zeroize)]
+struct Login {
+    username: String,
+    password: String,
+    token: Vec<u8>,
+}
+
+fn assert_zeroize_on_drop<T: ZeroizeOnDrop>(_: &T) {}
+
+#[test]
+fn omitted_secrets_are_zeroizable() {
+    let full = Login {
+        username: "root".to_string(),
+        password: "hunter2".to_string(),
+        token: vec![1, 2, 3],
+    };
+
+    let (partial, mut omitted) = PartialLogin::from_login_with_omitted(full);
+    assert_eq!(partial.username, "root");
+    assert_zeroize_on_drop(&omitted);
+
+    let rebuilt = partial.to_login(omitted.password.clone(), omitted.token.clone());
+    assert_eq!(rebuilt.password, "hunter2");
+
+    omitted.zeroize();
+    assert!(omitted.password.is_empty());
+    assert!(omitted.token.is_empty());
+}